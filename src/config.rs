@@ -1,26 +1,210 @@
-use serde::Deserialize;
-use std::fs::{self, read, File};
+use chrono::{Datelike, NaiveDate, TimeDelta, Weekday};
+use serde::{de, Deserialize, Deserializer};
+use std::fs::read;
 use std::path::Path;
-use toml;
 
-#[derive(Deserialize)]
+use crate::error::Result;
+use crate::tasks::ChronoUnit;
+use crate::time;
+
+#[derive(Deserialize, Default)]
 pub struct Config {
     pub editor: Option<String>,
+    #[serde(default)]
+    pub targets: Targets,
+    ///How long tlog can go unused while a task is running before asking
+    ///what to do with the time, such as "8h". Never asks when not set.
+    #[serde(default, deserialize_with = "duration")]
+    pub idle_threshold: Option<TimeDelta>,
+    #[serde(default)]
+    pub rounding: Rounding,
+}
+
+///How reported time is rounded up for billing, set in the `[rounding]`
+///section of config.toml. The log itself always keeps the exact times.
+#[derive(Deserialize, Default)]
+pub struct Rounding {
+    ///Round up to this many minutes, such as 6, 15 or 30.
+    pub minutes: Option<u32>,
+    #[serde(default)]
+    pub per: RoundPer,
+}
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundPer {
+    ///Round every logged entry.
+    #[default]
+    Entry,
+    ///Round the total of each day.
+    Day,
+}
+
+fn duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<TimeDelta>, D::Error> {
+    let value: Option<String> = Option::deserialize(deserializer)?;
+    value
+        .map(|value| time::parse_duration(&value).map_err(de::Error::custom))
+        .transpose()
+}
+
+///Hours to log, set in the `[targets]` section of config.toml. A weekday
+///such as `friday = 4` overrides `daily_hours` on that day.
+#[derive(Deserialize, Default)]
+pub struct Targets {
+    pub daily_hours: Option<f64>,
+    pub weekly_hours: Option<f64>,
+    pub monday: Option<f64>,
+    pub tuesday: Option<f64>,
+    pub wednesday: Option<f64>,
+    pub thursday: Option<f64>,
+    pub friday: Option<f64>,
+    pub saturday: Option<f64>,
+    pub sunday: Option<f64>,
 }
 
 impl Config {
-    pub fn new(config_file: &str) -> Self {
+    pub fn new(config_file: &str) -> Result<Self> {
         let path = Path::new(config_file);
 
         if path.is_file() {
-            let config: String = String::from_utf8_lossy(&read(path).unwrap())
-                .parse()
-                .unwrap();
+            let config = String::from_utf8_lossy(&read(path)?).into_owned();
+
+            let config: Config = toml::from_str(&config)?;
+            return Ok(config);
+        }
+
+        Ok(Config::default())
+    }
+}
+
+impl Targets {
+    ///Seconds to log on the given day, none on days off.
+    pub fn daily(&self, date: NaiveDate) -> Option<i64> {
+        let hours = match date.weekday() {
+            Weekday::Mon => self.monday,
+            Weekday::Tue => self.tuesday,
+            Weekday::Wed => self.wednesday,
+            Weekday::Thu => self.thursday,
+            Weekday::Fri => self.friday,
+            Weekday::Sat => self.saturday,
+            Weekday::Sun => self.sunday,
+        };
+        hours
+            .or(self.daily_hours)
+            .map(Self::seconds)
+            .filter(|seconds| *seconds > 0)
+    }
+
+    ///Seconds to log between the dates. A week uses `weekly_hours` when it is
+    ///set, otherwise the daily targets of each day are added up with days
+    ///without a target counting as zero.
+    pub fn between(&self, unit: &ChronoUnit, from: NaiveDate, to: NaiveDate) -> Option<i64> {
+        if let (ChronoUnit::Week, Some(hours)) = (unit, self.weekly_hours) {
+            return Some(Self::seconds(hours));
+        }
+        let days: Vec<Option<i64>> = from
+            .iter_days()
+            .take_while(|day| *day <= to)
+            .map(|day| self.daily(day))
+            .collect();
+        if days.iter().all(Option::is_none) {
+            return None;
+        }
+        Some(days.into_iter().flatten().sum())
+    }
+
+    fn seconds(hours: f64) -> i64 {
+        (hours * 60.0 * 60.0).round() as i64
+    }
+}
+
+impl Rounding {
+    ///Round seconds up to the configured increment.
+    pub fn round(&self, seconds: i64) -> i64 {
+        match self.minutes {
+            Some(minutes) if minutes > 0 => {
+                let increment = minutes as i64 * 60;
+                (seconds + increment - 1).div_euclid(increment) * increment
+            }
+            _ => seconds,
+        }
+    }
 
-            let config: Config = toml::from_str(&config).unwrap();
-            return config;
+    ///Round the seconds of a single entry, when rounding per entry.
+    pub fn entry(&self, seconds: i64) -> i64 {
+        match self.per {
+            RoundPer::Entry => self.round(seconds),
+            RoundPer::Day => seconds,
         }
+    }
+
+    ///Describes the rounding, none when time is not rounded.
+    pub fn description(&self) -> Option<String> {
+        let minutes = self.minutes.filter(|minutes| *minutes > 0)?;
+        let per = match self.per {
+            RoundPer::Entry => "entry",
+            RoundPer::Day => "day",
+        };
+        Some(format!("rounded up to {minutes} minutes per {per}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::date;
+
+    #[test]
+    fn test_idle_threshold() {
+        let config: Config = toml::from_str("idle_threshold = \"1h30m\"").unwrap();
+        assert_eq!(config.idle_threshold, Some(TimeDelta::minutes(90)));
+        assert!(toml::from_str::<Config>("idle_threshold = \"soon\"").is_err());
+        assert!(toml::from_str::<Config>("")
+            .unwrap()
+            .idle_threshold
+            .is_none());
+    }
+
+    #[test]
+    fn test_rounding() {
+        let config: Config = toml::from_str("[rounding]\nminutes = 15\n").unwrap();
+        let rounding = config.rounding;
+        assert_eq!(rounding.per, RoundPer::Entry);
+        assert_eq!(rounding.round(0), 0);
+        assert_eq!(rounding.round(1), 900);
+        assert_eq!(rounding.round(900), 900);
+        assert_eq!(rounding.round(901), 1800);
+
+        let config: Config = toml::from_str("[rounding]\nminutes = 6\nper = \"day\"\n").unwrap();
+        assert_eq!(config.rounding.entry(61), 61);
+        assert_eq!(config.rounding.round(61), 360);
+        assert_eq!(Rounding::default().round(61), 61);
+    }
+
+    #[test]
+    fn test_targets() {
+        let config: Config =
+            toml::from_str("[targets]\ndaily_hours = 7.5\nfriday = 4\nsaturday = 0\nsunday = 0\n")
+                .unwrap();
+        let targets = config.targets;
+        assert_eq!(targets.daily(date("2026-10-01")), Some(27000));
+        assert_eq!(targets.daily(date("2026-10-02")), Some(14400));
+        assert_eq!(targets.daily(date("2026-10-03")), None);
+
+        //Monday to Sunday, 4 * 7.5 + 4 hours.
+        let (from, to) = (date("2026-09-28"), date("2026-10-04"));
+        assert_eq!(
+            targets.between(&ChronoUnit::Week, from, to),
+            Some(34 * 3600)
+        );
 
-        Config { editor: None }
+        let targets = Targets {
+            weekly_hours: Some(37.5),
+            ..Targets::default()
+        };
+        assert_eq!(targets.between(&ChronoUnit::Week, from, to), Some(135000));
+        assert_eq!(targets.between(&ChronoUnit::Day, from, from), None);
     }
 }
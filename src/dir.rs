@@ -1,14 +1,16 @@
 use std::borrow::Borrow;
-use std::fs::OpenOptions;
 use std::fs::{self, read, File};
-use std::io::Write;
+use std::io::{self, Write};
 use std::path::Path;
 
-use chrono::prelude::Local;
+use chrono::prelude::{DateTime, Local};
 
-use chrono::Datelike;
+use chrono::{Datelike, NaiveDate};
 use homedir::get_my_home;
 
+use crate::error::Result;
+use crate::projects::Projects;
+
 #[derive(Debug)]
 pub struct Dir {
     pub config_file: String,
@@ -24,37 +26,39 @@ pub struct Dir {
     pub current_file: String,
     ///This stores the last active task.
     pub last_file: String,
-    pub current_month: String,
-    pub current_year: String,
+    ///This stores the paused task, so it can be resumed later.
+    pub paused_file: String,
+    ///This stores when tlog was last run, used to notice idle time.
+    pub last_seen_file: String,
 }
 
 impl Dir {
-    //todo: handle option unwraping in this method.
-    pub fn new(data_dir: Option<String>) -> Self {
+    pub fn new(data_dir: Option<String>) -> Result<Self> {
         let time_tracker_dir = match data_dir {
             Some(dir) => dir,
             None => {
-                let home_dir = get_my_home().unwrap().unwrap();
-                let time_tracker_dir = Self::time_tracker_dir(home_dir.to_str().unwrap());
-                time_tracker_dir
+                let home_dir = get_my_home()
+                    .map_err(io::Error::other)?
+                    .ok_or_else(|| io::Error::other("Could not locate home directory"))?;
+                Self::time_tracker_dir(&home_dir.to_string_lossy())?
             }
         };
 
         let year = Local::now().year().to_string();
         let month = Local::now().month().to_string();
 
-        let year_dir = Self::year_dir(&time_tracker_dir, &year);
+        let year_dir = Self::year_dir(&time_tracker_dir, &year)?;
 
-        Dir {
+        Ok(Dir {
             config_file: format!("{}/{}", &time_tracker_dir, "config.toml"),
-            projects_file: format!("{}/{}", &time_tracker_dir, "projects"),
+            projects_file: format!("{}/{}", &time_tracker_dir, "projects.toml"),
             log_file: format!("{}/{}", year_dir, month),
             current_file: format!("{}/{}", time_tracker_dir, "current"),
             last_file: format!("{}/{}", time_tracker_dir, "last"),
-            current_year: year,
-            current_month: month,
+            paused_file: format!("{}/{}", time_tracker_dir, "paused"),
+            last_seen_file: format!("{}/{}", time_tracker_dir, "last_seen"),
             time_tracker_dir,
-        }
+        })
     }
 
     pub fn month_file(&self, year: &str, month: &str) -> String {
@@ -62,77 +66,90 @@ impl Dir {
     }
 
     //Creates time_tracker home dir if it does not exist.
-    fn time_tracker_dir(home: &str) -> String {
+    fn time_tracker_dir(home: &str) -> Result<String> {
         let dir = format!("{}/{}", home, ".time_tracker");
         let path = Path::new(&dir);
-        let dir = path
-            .to_str()
-            .expect(&format!("Could not locate directory {}", dir));
         if !path.exists() {
-            fs::create_dir(path).expect(&format!("Could not create path {}", dir))
+            fs::create_dir(path)?
         }
-        dir.to_owned()
+        Ok(dir)
     }
 
     //Creates dir for the current year if it does not exist
-    fn year_dir(time_tacker_dir: &str, year: &str) -> String {
+    fn year_dir(time_tacker_dir: &str, year: &str) -> Result<String> {
         let dir = format!("{}/{}", time_tacker_dir, year);
         let path = Path::new(&dir);
-        let dir = path
-            .to_str()
-            .expect(&format!("Could not locate directory {}", dir));
         if !path.exists() {
-            fs::create_dir(path).expect(&format!("Could not create path {}", dir))
+            fs::create_dir(path)?
         }
-        dir.to_owned()
+        Ok(dir)
     }
 
-    fn read(file: &str) -> String {
+    fn read(file: &str) -> Result<String> {
         let path = Path::new(file);
 
         if path.is_file() {
-            let file_contents: String = String::from_utf8_lossy(&read(path).unwrap())
-                .parse()
-                .unwrap();
-            file_contents
+            Ok(String::from_utf8_lossy(&read(path)?).into_owned())
         } else {
-            Self::write(file, "").expect(&format!("Could not read file {}", file));
-            "".to_owned()
+            Self::write(file, "")?;
+            Ok("".to_owned())
         }
     }
 
-    pub fn write(file: &str, data: &str) -> Result<(), std::io::Error> {
-        let mut file = File::create(file).unwrap();
-        file.write_all(data.as_bytes()).unwrap();
+    pub fn write(file: &str, data: &str) -> Result<()> {
+        let mut file = File::create(file)?;
+        file.write_all(data.as_bytes())?;
         Ok(())
     }
 
-    pub fn write_line(file: &str, data: &str) -> Result<(), std::io::Error> {
-        let mut file = OpenOptions::new().append(true).create(true).open(file)?;
-
-        // Write a new line to the file
-        writeln!(file, "{}", data)?;
+    ///Read the projects file. Projects used to be stored as a comma
+    ///separated list in `projects`, that file is migrated the first time and
+    ///kept as `projects.bak`.
+    pub fn read_project_file(&self) -> Result<String> {
+        let old_file = format!("{}/{}", self.time_tracker_dir, "projects");
+        if !Path::new(&self.projects_file).exists() && Path::new(&old_file).is_file() {
+            let names = fs::read_to_string(&old_file)?;
+            Self::write(&self.projects_file, &Projects::from_names(&names)?)?;
+            fs::rename(&old_file, format!("{old_file}.bak"))?;
+        }
+        Self::read(self.projects_file.borrow())
+    }
 
+    fn remove_file(&self, file: &str) -> Result<()> {
+        fs::remove_file(file)?;
         Ok(())
     }
 
-    pub fn read_project_file(&self) -> String {
-        Self::read(&self.projects_file.borrow())
+    pub fn remove_current_file(&self) -> Result<()> {
+        self.remove_file(&self.current_file)
+    }
+
+    pub fn remove_paused_file(&self) -> Result<()> {
+        if Path::new(&self.paused_file).exists() {
+            self.remove_file(&self.paused_file)?
+        }
+        Ok(())
     }
 
-    fn remove_file(&self, file: &str) {
-        let test = fs::remove_file(file).unwrap();
+    pub fn read_last_seen(&self) -> Result<Option<DateTime<Local>>> {
+        if !Path::new(&self.last_seen_file).is_file() {
+            return Ok(None);
+        }
+        let last_seen = fs::read_to_string(&self.last_seen_file)?;
+        Ok(DateTime::parse_from_rfc3339(last_seen.trim())
+            .ok()
+            .map(|last_seen| last_seen.with_timezone(&Local)))
     }
 
-    pub fn remove_current_file(&self) {
-        self.remove_file(&self.current_file)
+    pub fn write_last_seen(&self, at: DateTime<Local>) -> Result<()> {
+        Self::write(&self.last_seen_file, &at.to_rfc3339())
     }
 
-    pub fn get_log_file_location(&self) -> String {
-        let path = format!(
-            "{}/{}/{}",
-            self.time_tracker_dir, self.current_year, self.current_month
-        );
-        path
+    ///The log file tasks on the given date are stored in. Creates the year
+    ///directory if it does not exist.
+    pub fn log_file_for(&self, date: NaiveDate) -> Result<String> {
+        let year = date.year().to_string();
+        Self::year_dir(&self.time_tracker_dir, &year)?;
+        Ok(self.month_file(&year, &date.month().to_string()))
     }
 }
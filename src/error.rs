@@ -0,0 +1,143 @@
+use std::fmt;
+use std::io;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    ///Reading or writing a file in the data directory failed.
+    Io(io::Error),
+    ///A log, current or last file could not be parsed.
+    Csv(csv::Error),
+    ///The config file could not be parsed.
+    Toml(toml::de::Error),
+    ///The command needs a task to be running.
+    NoCurrentTask,
+    UnknownProject(String),
+    ///The project exists but is archived, so no new time can be tracked on it.
+    ArchivedProject(String),
+    ///No task name was given and there was no default to fall back to.
+    NoTaskName,
+    ClipboardUnavailable(arboard::Error),
+    ///No project was given and there was no default to fall back to.
+    NoProject,
+}
+
+impl Error {
+    ///Exit code for the error, so scripts can tell the errors apart. Codes
+    ///start at 3, clap exits with 2 on usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Io(_) => 3,
+            Error::Csv(_) => 4,
+            Error::Toml(_) => 5,
+            Error::NoCurrentTask => 6,
+            Error::UnknownProject(_) => 7,
+            Error::NoTaskName => 8,
+            Error::ClipboardUnavailable(_) => 9,
+            Error::ArchivedProject(_) => 10,
+            Error::NoProject => 11,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "Could not read or write tracking files: {}", e),
+            Error::Csv(e) => write!(f, "Could not parse log file: {}", e),
+            Error::Toml(e) => write!(f, "Could not parse config file: {}", e),
+            Error::NoCurrentTask => write!(f, "No task is currently being tracked."),
+            Error::UnknownProject(name) => write!(
+                f,
+                "Project {:?} does not exist, add it with `tlog projects add`.",
+                name
+            ),
+            Error::ArchivedProject(name) => write!(
+                f,
+                "Project {:?} is archived, restore it with `tlog projects unarchive`.",
+                name
+            ),
+            Error::NoTaskName => write!(f, "Please enter task name!"),
+            Error::NoProject => write!(f, "Please enter a project!"),
+            Error::ClipboardUnavailable(e) => write!(f, "Could not read clipboard: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        Error::Csv(e)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(e: toml::de::Error) -> Self {
+        Error::Toml(e)
+    }
+}
+
+impl From<arboard::Error> for Error {
+    fn from(e: arboard::Error) -> Self {
+        Error::ClipboardUnavailable(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors() -> Vec<Error> {
+        vec![
+            Error::Io(io::Error::new(io::ErrorKind::NotFound, "missing")),
+            Error::Csv(csv::Error::from(io::Error::other("bad row"))),
+            Error::Toml(toml::from_str::<toml::Table>("a =").unwrap_err()),
+            Error::NoCurrentTask,
+            Error::UnknownProject("tlog".to_owned()),
+            Error::NoTaskName,
+            Error::ClipboardUnavailable(arboard::Error::ContentNotAvailable),
+            Error::ArchivedProject("tlog".to_owned()),
+            Error::NoProject,
+        ]
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let codes: Vec<i32> = errors().iter().map(Error::exit_code).collect();
+        assert_eq!(codes, vec![3, 4, 5, 6, 7, 8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Error::Io(io::Error::new(io::ErrorKind::NotFound, "missing")).to_string(),
+            "Could not read or write tracking files: missing"
+        );
+        assert_eq!(
+            Error::NoCurrentTask.to_string(),
+            "No task is currently being tracked."
+        );
+        assert_eq!(
+            Error::UnknownProject("tlog".to_owned()).to_string(),
+            "Project \"tlog\" does not exist, add it with `tlog projects add`."
+        );
+        assert_eq!(
+            Error::ArchivedProject("tlog".to_owned()).to_string(),
+            "Project \"tlog\" is archived, restore it with `tlog projects unarchive`."
+        );
+        assert_eq!(Error::NoTaskName.to_string(), "Please enter task name!");
+        assert_eq!(Error::NoProject.to_string(), "Please enter a project!");
+        let messages: Vec<String> = errors().iter().map(Error::to_string).collect();
+        assert!(messages[1].starts_with("Could not parse log file: "));
+        assert!(messages[2].starts_with("Could not parse config file: "));
+        assert!(messages[6].starts_with("Could not read clipboard: "));
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::prelude::{DateTime, Local};
+use chrono::TimeDelta;
+
+use crate::config::Config;
+use crate::dir::Dir;
+use crate::error::Result;
+use crate::input;
+use crate::out::task::TaskOut;
+use crate::task::Task;
+
+const KEEP: &str = "Keep the time";
+const SPECIFIC: &str = "Stop at a specific time";
+
+///Remember when tlog was run. When it has not been run for longer than the
+///idle threshold while a task was running, ask whether to keep that time
+///or to stop the task before it. Pass `prompt` false for commands that
+///already say when the task stops, they only update when tlog was run.
+///Returns whether the current task was stopped.
+pub fn check(dir: &Dir, config: &Config, prompt: bool) -> Result<bool> {
+    let now = Local::now();
+    let last_seen = dir.read_last_seen()?;
+    dir.write_last_seen(now)?;
+
+    let Some(threshold) = config.idle_threshold.filter(|_| prompt) else {
+        return Ok(false);
+    };
+    let Some(mut task) = Task::from_current(dir)? else {
+        return Ok(false);
+    };
+    let Some(seen) = idle_since(now, last_seen, task.start, threshold) else {
+        return Ok(false);
+    };
+
+    println!(
+        "\"{}\" has been running since {}, tlog was last used {}.",
+        task.name,
+        task.start.format("%Y-%m-%d %H:%M"),
+        seen.format("%Y-%m-%d %H:%M")
+    );
+    let stop_at_last_seen = format!("Stop at {}", seen.format("%Y-%m-%d %H:%M"));
+    let options = [KEEP, &stop_at_last_seen, SPECIFIC];
+    let selected = tui::menu("What should happen with the idle time?", &options);
+
+    let at = match selected.map(|index| options[index]) {
+        None | Some(KEEP) => return Ok(false),
+        Some(SPECIFIC) => match prompt_stop(&task, seen, now) {
+            Some(at) => at,
+            None => return Ok(false),
+        },
+        _ => seen,
+    };
+    task.complete(at)?;
+    println!("Stopped:");
+    TaskOut::current_task(&task);
+    Ok(true)
+}
+
+//When tlog was last seen, if that is longer than the threshold ago. A task
+//started after tlog was last run counts as seen when it started.
+fn idle_since(
+    now: DateTime<Local>,
+    last_seen: Option<DateTime<Local>>,
+    start: DateTime<Local>,
+    threshold: TimeDelta,
+) -> Option<DateTime<Local>> {
+    let seen = last_seen.map_or(start, |last_seen| last_seen.max(start));
+    (now - seen > threshold).then_some(seen)
+}
+
+//Ask when the task should have stopped, it has to be after it started.
+fn prompt_stop(
+    task: &Task,
+    default: DateTime<Local>,
+    now: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    let at = input::date_time("Stop at", default.format("%Y-%m-%d %H:%M").to_string())?;
+    if at <= task.start || at > now {
+        println!(
+            "The task has to stop between {} and now, keeping the time.",
+            task.start.format("%Y-%m-%d %H:%M")
+        );
+        return None;
+    }
+    Some(at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, at};
+
+    #[test]
+    fn test_idle_since() {
+        let threshold = TimeDelta::hours(8);
+        assert_eq!(idle_since(at(17), Some(at(10)), at(9), threshold), None);
+        assert_eq!(idle_since(at(18), Some(at(10)), at(9), threshold), None);
+        assert_eq!(
+            idle_since(at(19), Some(at(10)), at(9), threshold),
+            Some(at(10))
+        );
+        //Started after tlog was last run, or never run before.
+        assert_eq!(idle_since(at(17), Some(at(8)), at(9), threshold), None);
+        assert_eq!(
+            idle_since(at(18), Some(at(8)), at(9), threshold),
+            Some(at(9))
+        );
+        assert_eq!(idle_since(at(17), None, at(9), threshold), None);
+        assert_eq!(idle_since(at(18), None, at(9), threshold), Some(at(9)));
+    }
+
+    #[test]
+    fn test_check_without_prompt() {
+        let (_tmp, dir) = testing::temp_dir();
+        let config = Config {
+            idle_threshold: Some(TimeDelta::hours(8)),
+            ..Config::default()
+        };
+        dir.write_last_seen(Local::now() - TimeDelta::days(1))
+            .unwrap();
+
+        assert!(!check(&dir, &config, false).unwrap());
+        let last_seen = dir.read_last_seen().unwrap().unwrap();
+        assert!(Local::now() - last_seen < TimeDelta::minutes(1));
+    }
+}
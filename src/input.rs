@@ -1,5 +1,8 @@
+use chrono::prelude::{DateTime, Local};
 use std::io;
 
+use crate::time;
+
 pub fn confirm(text: String) -> bool {
     let mut input = String::new();
     println!("{} (y):", text);
@@ -28,3 +31,15 @@ pub fn input(text: &str, default_value: Option<String>) -> Option<String> {
         input => Some(input.to_owned()),
     }
 }
+
+///Prompt for a date and time such as "2026-10-02 09:30".
+pub fn date_time(text: &str, default: String) -> Option<DateTime<Local>> {
+    let value = input(text, Some(default))?;
+    match time::parse_date_time(&value) {
+        Ok(date_time) => Some(date_time),
+        Err(e) => {
+            println!("{e}");
+            None
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::{prelude::Local, NaiveDate};
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+
+use crate::dir::Dir;
+use crate::error::Result;
+use crate::input;
+use crate::out::task::TaskOut;
+use crate::projects::Projects;
+use crate::task::Task;
+use crate::tasks::Tasks;
+
+const HEADERS: [&str; 5] = ["Name", "TicketNumber", "Project", "Start", "End"];
+
+///All tasks in one month log file, so entries can be changed and the file
+///written back.
+pub struct MonthLog<'a> {
+    pub file: String,
+    headers: StringRecord,
+    pub tasks: Vec<Task<'a>>,
+}
+
+impl<'a> MonthLog<'a> {
+    pub fn read(dir: &'a Dir, file: String) -> Result<Self> {
+        let mut headers = StringRecord::from(HEADERS.to_vec());
+        let mut tasks = vec![];
+
+        if Path::new(&file).exists() {
+            let mut rdr = ReaderBuilder::new().has_headers(true).from_path(&file)?;
+            headers = rdr.headers()?.clone();
+            tasks = Tasks::read_tasks(dir, &mut rdr)?;
+        }
+
+        Ok(Self {
+            file,
+            headers,
+            tasks,
+        })
+    }
+
+    ///Rewrite the month file. It's written to a temporary file first and
+    ///then moved over the original, so a failed write leaves it untouched.
+    pub fn save(&self) -> Result<()> {
+        let tmp_file = format!("{}.tmp", self.file);
+        let mut wtr = WriterBuilder::new()
+            .has_headers(false)
+            .from_path(&tmp_file)?;
+        wtr.write_record(&self.headers)?;
+        for task in &self.tasks {
+            wtr.serialize(task)?;
+        }
+        wtr.flush()?;
+        drop(wtr);
+
+        fs::rename(tmp_file, &self.file)?;
+        Ok(())
+    }
+}
+
+fn label(task: &Task) -> String {
+    let ticket = match &task.ticket_number {
+        Some(t) => format!(" <{}>", t),
+        _ => "".to_owned(),
+    };
+    let end = match task.end {
+        Some(end) => end.format("%H:%M").to_string(),
+        None => "".to_owned(),
+    };
+    format!(
+        "{} {}-{} {}{} [{}]",
+        task.start.format("%Y-%m-%d"),
+        task.start.format("%H:%M"),
+        end,
+        task.name,
+        ticket,
+        task.project
+    )
+}
+
+fn read_logs(dir: &Dir, from: NaiveDate, to: NaiveDate) -> Result<Vec<MonthLog<'_>>> {
+    Tasks::months_between(from, to)
+        .into_iter()
+        .map(|(year, month)| {
+            MonthLog::read(dir, dir.month_file(&year.to_string(), &month.to_string()))
+        })
+        .collect()
+}
+
+//The position of the month log and the task in it, for every task that
+//started between the dates.
+fn entries(logs: &[MonthLog], from: NaiveDate, to: NaiveDate) -> Vec<(usize, usize)> {
+    let entries: Vec<(usize, usize)> = logs
+        .iter()
+        .enumerate()
+        .flat_map(|(m, log)| {
+            log.tasks
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| {
+                    let start = task.start.date_naive();
+                    from <= start && start <= to
+                })
+                .map(move |(i, _)| (m, i))
+        })
+        .collect();
+
+    if entries.is_empty() {
+        println!("No tasks logged from {from} to {to}.");
+    }
+    entries
+}
+
+fn labels(logs: &[MonthLog], entries: &[(usize, usize)]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|(m, i)| label(&logs[*m].tasks[*i]))
+        .collect()
+}
+
+//Let the user pick a task that started between the dates. Returns the
+//position of the month log and the task in it.
+fn select(logs: &[MonthLog], from: NaiveDate, to: NaiveDate) -> Option<(usize, usize)> {
+    let entries = entries(logs, from, to);
+    let labels = labels(logs, &entries);
+    let selected = tui::menu("Select task:", &labels)?;
+    Some(entries[selected])
+}
+
+//Let the user pick any number of tasks that started between the dates.
+fn select_many(logs: &[MonthLog], from: NaiveDate, to: NaiveDate) -> Vec<(usize, usize)> {
+    let entries = entries(logs, from, to);
+    let labels = labels(logs, &entries);
+    let selected = tui::multi_select("Select tasks, space to toggle:", &labels);
+    selected
+        .unwrap_or_default()
+        .into_iter()
+        .map(|selected| entries[selected])
+        .collect()
+}
+
+//Prompt for every field of the task, using the current values as defaults.
+fn prompt_edit<'a>(projects: &Projects, task: &Task<'a>) -> Option<Task<'a>> {
+    let mut task = task.clone();
+    let format = "%Y-%m-%d %H:%M";
+
+    task.name = input::input("Task name", Some(task.name))?;
+    task.ticket_number = input::input("Ticket Number (- to clear)", task.ticket_number)
+        .filter(|ticket| ticket != "-");
+    let project = task.project.clone();
+    task.project = Task::set_project(projects, &Some(task.project), Some(&project)).ok()?;
+    task.start = input::date_time("Start", task.start.format(format).to_string())?;
+    let end = task.end.unwrap_or_else(Local::now);
+    let end = input::date_time("End", end.format(format).to_string())?;
+
+    if end <= task.start {
+        println!("The task has to end after it starts.");
+        return None;
+    }
+    task.end = Some(end);
+    Some(task)
+}
+
+///Pick a logged task between the dates and change its name, ticket,
+///project, start or end.
+pub fn edit(dir: &Dir, projects: &Projects, from: NaiveDate, to: NaiveDate) -> Result<()> {
+    let mut logs = read_logs(dir, from, to)?;
+    let Some((m, i)) = select(&logs, from, to) else {
+        return Ok(());
+    };
+    let Some(task) = prompt_edit(projects, &logs[m].tasks[i]) else {
+        println!("Nothing changed.");
+        return Ok(());
+    };
+
+    let log = &mut logs[m];
+    if task.log_file()? == log.file {
+        log.tasks[i] = task.clone();
+        log.save()?;
+    } else {
+        //The task moved to another month. Add it there before removing it
+        //here, so a failed write can't lose it.
+        task.write_to_log_file()?;
+        log.tasks.remove(i);
+        log.save()?;
+    }
+    TaskOut::current_task(&task);
+    Ok(())
+}
+
+///Pick logged tasks between the dates and remove them from the log.
+pub fn delete(dir: &Dir, from: NaiveDate, to: NaiveDate) -> Result<()> {
+    let mut logs = read_logs(dir, from, to)?;
+    let selected = select_many(&logs, from, to);
+    if selected.is_empty() {
+        return Ok(());
+    }
+
+    let labels = labels(&logs, &selected);
+    labels.iter().for_each(|label| println!(" - {label}"));
+    if !input::confirm(format!(
+        "Are you sure you wish to delete these {} tasks",
+        labels.len()
+    )) {
+        return Ok(());
+    }
+
+    //Remove from the back so the positions of the other tasks stay valid.
+    for (m, i) in selected.iter().rev() {
+        logs[*m].tasks.remove(*i);
+    }
+    let mut months: Vec<usize> = selected.iter().map(|(m, _)| *m).collect();
+    months.dedup();
+    for m in months {
+        logs[m].save()?;
+    }
+    println!("Deleted {} tasks.", labels.len());
+    Ok(())
+}
+
+//Every month log file in the data directory.
+fn month_files(dir: &Dir) -> Result<Vec<String>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(&dir.time_tracker_dir)? {
+        let entry = entry?;
+        let year = entry.file_name().to_string_lossy().into_owned();
+        if !entry.path().is_dir() || year.parse::<i32>().is_err() {
+            continue;
+        }
+        for month in 1..=12 {
+            let file = dir.month_file(&year, &month.to_string());
+            if Path::new(&file).is_file() {
+                files.push(file);
+            }
+        }
+    }
+    Ok(files)
+}
+
+//Change the project of the tasks in a log file, returns how many changed.
+fn rename_in(dir: &Dir, file: String, old: &str, new: &str) -> Result<usize> {
+    let mut log = MonthLog::read(dir, file)?;
+    let mut count = 0;
+    for task in log.tasks.iter_mut().filter(|task| task.project == old) {
+        task.project = new.to_owned();
+        count += 1;
+    }
+    if count > 0 {
+        log.save()?;
+    }
+    Ok(count)
+}
+
+///Change the project of every logged task, and of the current, paused and
+///last task, from `old` to `new`. Returns how many logged tasks changed.
+pub fn rename_project(dir: &Dir, old: &str, new: &str) -> Result<usize> {
+    let mut renamed = 0;
+    for file in month_files(dir)? {
+        renamed += rename_in(dir, file, old, new)?;
+    }
+    for file in [&dir.current_file, &dir.paused_file, &dir.last_file] {
+        if Path::new(file).is_file() {
+            rename_in(dir, file.clone(), old, new)?;
+        }
+    }
+    Ok(renamed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, local};
+
+    //A row for a task logged from 09:00 to 10:00 local time on the day.
+    fn row(name: &str, ticket: &str, project: &str, day: &str) -> String {
+        let (start, end) = (format!("{day} 09:00"), format!("{day} 10:00"));
+        testing::row(name, ticket, project, &start, &end)
+    }
+
+    #[test]
+    fn test_save_keeps_headers() {
+        let (_tmp, dir) = testing::temp_dir();
+        let file = dir.month_file("2026", "10");
+        let rows = [
+            row("a", "", "p", "2026-10-02"),
+            row("b", "T-1", "p", "2026-10-03"),
+        ];
+        testing::write_log(&dir, "2026", "10", &rows);
+
+        let mut log = MonthLog::read(&dir, file.clone()).unwrap();
+        log.tasks.remove(0);
+        log.save().unwrap();
+        let contents = fs::read_to_string(&file).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "Name,TicketNumber,Project,Start,End");
+        assert!(lines[1].starts_with("b,T-1,p,2026-10-03T"));
+
+        log.tasks.clear();
+        log.save().unwrap();
+        let contents = fs::read_to_string(&file).unwrap();
+        assert_eq!(contents, "Name,TicketNumber,Project,Start,End\n");
+        assert!(!Path::new(&format!("{file}.tmp")).exists());
+    }
+
+    #[test]
+    fn test_rename_project() {
+        let (_tmp, dir) = testing::temp_dir();
+        for (year, month, day) in [("2025", "12", "2025-12-30"), ("2026", "1", "2026-01-02")] {
+            let rows = [row("a", "", "old", day), row("b", "", "other", day)];
+            testing::write_log(&dir, year, month, &rows);
+        }
+        fs::write(
+            &dir.current_file,
+            format!(
+                "{}c,,old,{},\n",
+                testing::HEADER,
+                local("2026-01-03 09:00").to_rfc3339()
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(rename_project(&dir, "old", "new").unwrap(), 2);
+        let log = MonthLog::read(&dir, dir.month_file("2025", "12")).unwrap();
+        let projects: Vec<_> = log.tasks.iter().map(|t| t.project.as_str()).collect();
+        assert_eq!(projects, vec!["new", "other"]);
+        let current = Task::from_current(&dir).unwrap().unwrap();
+        assert_eq!(current.project, "new");
+    }
+}
@@ -1,20 +1,34 @@
 mod config;
 mod dir;
+mod error;
+mod idle;
 mod input;
+mod log;
+mod markdown;
 mod open;
 mod out;
 mod projects;
 mod task;
 mod tasks;
+#[cfg(test)]
+mod testing;
+mod time;
+mod ui;
 
-use chrono::Duration;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeDelta};
+use error::{Error, Result};
+use std::fs;
+use std::process;
 use task::Task;
-use tasks::{ChronoUnit, Tasks};
+use tasks::{ChronoUnit, GroupBy, Tasks};
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use open::open_file_in_editor;
 
-use crate::out::{projects::ProjectsOut, task::pretty_duration, task::TaskOut};
+use crate::out::{
+    projects::ProjectsOut, report::ReportOut, target::TargetOut, task::pretty_duration,
+    task::TaskOut, Format,
+};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -25,6 +39,9 @@ struct Cli {
     #[arg(long)]
     ///Where would you like to save the data for the time tracker.
     data_dir: Option<String>,
+    #[arg(long, global = true, value_enum, default_value_t = Format::Table)]
+    ///How to print task, project and report listings.
+    format: Format,
     #[command(subcommand)]
     command: Commands,
 }
@@ -40,8 +57,36 @@ enum Commands {
     ///Open the latest log file in your default editor or specified
     ///editor in config.toml
     Open,
-    ///See how much time you have logged today
-    Logged,
+    ///Full screen view of the current task and today's tasks
+    Ui,
+    ///See how much time you have logged today, or this week, month or year
+    Logged {
+        #[arg(long, conflicts_with_all = ["month", "year"])]
+        /// Show this week instead, broken down per day.
+        week: bool,
+        #[arg(long, conflicts_with = "year")]
+        /// Show this month instead, broken down per day.
+        month: bool,
+        #[arg(long)]
+        /// Show this year instead, broken down per day.
+        year: bool,
+    },
+    ///Edit or delete tasks you have logged.
+    #[command(subcommand)]
+    Log(LogCmd),
+    ///Report the tasks logged between two dates.
+    Report {
+        #[arg(long)]
+        /// First day of the report, formatted as YYYY-MM-DD.
+        from: NaiveDate,
+        #[arg(long)]
+        /// Last day of the report, formatted as YYYY-MM-DD. Defaults to today.
+        to: Option<NaiveDate>,
+        #[arg(long, value_enum)]
+        /// Sum the time spent per project, ticket or task name instead of
+        /// listing every task.
+        group_by: Option<GroupBy>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -49,9 +94,60 @@ enum Projects {
     ///List current available tasks
     List,
     ///Add a projects
-    Add { name: String },
+    Add {
+        name: String,
+        #[arg(short, long)]
+        ///What the project is about.
+        description: Option<String>,
+    },
     ///Delete a project
     Delete { name: String },
+    ///Hide a project when starting tasks, logged tasks keep it
+    Archive { name: String },
+    ///Make an archived project available again
+    Unarchive { name: String },
+    ///Rename a project
+    Rename {
+        old: String,
+        new: String,
+        #[arg(long)]
+        ///Also change the project of logged tasks.
+        rewrite_logs: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum LogCmd {
+    ///Select a logged task and change its name, ticket, project, start or end.
+    Edit {
+        #[command(flatten)]
+        period: Period,
+    },
+    ///Select logged tasks and delete them.
+    Delete {
+        #[command(flatten)]
+        period: Period,
+    },
+}
+
+///Which logged tasks to select from.
+#[derive(Args, Debug)]
+struct Period {
+    #[arg(long)]
+    /// First day to list tasks from, formatted as YYYY-MM-DD. Defaults to the
+    /// first day of this month.
+    from: Option<NaiveDate>,
+    #[arg(long)]
+    /// Last day to list tasks from, formatted as YYYY-MM-DD. Defaults to today.
+    to: Option<NaiveDate>,
+}
+
+impl Period {
+    fn dates(&self) -> (NaiveDate, NaiveDate) {
+        let today = Local::now().date_naive();
+        let from = self.from.unwrap_or_else(|| today.with_day(1).unwrap());
+        (from, self.to.unwrap_or(today))
+    }
 }
 
 #[derive(Subcommand, Debug)]
@@ -64,25 +160,76 @@ enum TaskCmd {
         /// tasks you have logged time against in the past. It only list
         /// tasks done this month
         list: bool,
-        #[arg(short, long)]
+        #[arg(short, long, value_name = "FILE")]
         /// Select task from a markdown file. It will find all lines that
         /// start with:
         /// " - [ ]" and list them as a task.
-        mark_down: bool,
+        mark_down: Option<String>,
         /// Select heading the checked items should come from in markdown
         /// file. It will use all lines up until the next heading.
-        #[arg(long)]
+        #[arg(long, requires = "mark_down")]
         mark_down_heading: bool,
         #[arg(short, long)]
         /// Use what is in your clipboard for the name of the task.
         clip_board: bool,
+        #[command(flatten)]
+        when: When,
+    },
+    ///Log a task that has already happened, for example a meeting you forgot
+    ///to track. Use --to or --for to set when it ended.
+    Add {
+        /// Name of the task, you will be prompted for it if it's not given.
+        name: Option<String>,
+        #[arg(long, value_parser = time::parse_time)]
+        /// Time the task started, formatted as HH:MM.
+        at: NaiveTime,
+        #[arg(long, value_parser = time::parse_time, required_unless_present = "duration")]
+        /// Time the task ended, formatted as HH:MM.
+        to: Option<NaiveTime>,
+        #[arg(long = "for", value_parser = time::parse_duration, conflicts_with = "to")]
+        /// How long the task took, for example 45m or 1h30m.
+        duration: Option<TimeDelta>,
+        #[arg(long)]
+        /// Day the task happened on, formatted as YYYY-MM-DD. Defaults to today.
+        date: Option<NaiveDate>,
+        #[arg(short, long)]
+        /// Ticket number of the task.
+        ticket: Option<String>,
+        #[arg(short, long)]
+        /// Project of the task, you will be prompted for it if it's not given.
+        project: Option<String>,
     },
     ///Stop current task.
-    Stop,
+    Stop {
+        #[command(flatten)]
+        when: When,
+    },
+    ///Pause the current task, for example over lunch. The time so far is
+    ///logged and the task can be resumed later.
+    Pause {
+        #[command(flatten)]
+        when: When,
+    },
+    ///Resume the paused task with the same name, ticket and project.
+    Resume {
+        #[command(flatten)]
+        when: When,
+    },
     ///Output time spent on current task
     Current,
 }
 
+///Start or stop a task at an earlier time than now.
+#[derive(Args, Debug)]
+struct When {
+    #[arg(long, value_parser = time::parse_time, conflicts_with = "ago")]
+    /// Time it happened today, formatted as HH:MM.
+    at: Option<NaiveTime>,
+    #[arg(long, value_parser = time::parse_duration)]
+    /// How long ago it happened, for example 10m or 1h15m.
+    ago: Option<TimeDelta>,
+}
+
 #[derive(Parser)]
 struct ProjectAdd {
     name: Option<String>,
@@ -98,103 +245,594 @@ fn is_debug() -> bool {
     false
 }
 
-fn complete_current_task(dir: &dir::Dir) {
-    let current_task = Task::from_current(dir);
+fn resolve_when(when: &When) -> Option<DateTime<Local>> {
+    match time::resolve(when.at, when.ago) {
+        Ok(at) => Some(at),
+        Err(e) => {
+            println!("{e}");
+            None
+        }
+    }
+}
+
+///Complete the current task, if there is one, at the given time. Returns
+///false when the current task started after that time. Any paused task is
+///discarded as another task is taking its place.
+fn complete_current_task(dir: &dir::Dir, at: DateTime<Local>) -> Result<bool> {
+    let current_task = Task::from_current(dir)?;
     if let Some(mut current_task) = current_task {
-        current_task.complete()
+        if at < current_task.start {
+            println!(
+                "Current task {:?} started at {}, it can't be stopped before that.",
+                current_task.name,
+                current_task.start.format("%H:%M")
+            );
+            return Ok(false);
+        }
+        current_task.complete(at)?;
+    }
+    dir.remove_paused_file()?;
+    Ok(true)
+}
+
+//With --at or --ago the command already says when the current task
+//ended, so it doesn't ask about idle time.
+fn asks_about_idle_time(command: &Commands) -> bool {
+    match command {
+        Commands::Task(
+            TaskCmd::Start { when, .. }
+            | TaskCmd::Stop { when }
+            | TaskCmd::Pause { when }
+            | TaskCmd::Resume { when },
+        ) => when.at.is_none() && when.ago.is_none(),
+        _ => true,
     }
 }
+
+fn start_from_mark_down(
+    dir: &dir::Dir,
+    projects: &projects::Projects,
+    file: &str,
+    heading: bool,
+    at: DateTime<Local>,
+) -> Result<()> {
+    let contents = fs::read_to_string(file)?;
+
+    let items = if heading {
+        let headings = markdown::headings(&contents);
+        if headings.is_empty() {
+            println!("No headings found in {file:?}.");
+            return Ok(());
+        }
+        let Some(index) = tui::menu("Select heading:", &headings) else {
+            return Ok(());
+        };
+        markdown::unchecked_items_under(&contents, &headings[index])
+    } else {
+        markdown::unchecked_items(&contents)
+    };
+
+    if items.is_empty() {
+        println!("No unchecked items found in {file:?}.");
+        return Ok(());
+    }
+
+    let Some(index) = tui::menu("Select task:", &items) else {
+        return Ok(());
+    };
+    if !complete_current_task(dir, at)? {
+        return Ok(());
+    }
+
+    let task = Task::new_with_name(dir, projects, items[index].to_owned(), at)?;
+    TaskOut::current_task(&task);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_task(
+    dir: &dir::Dir,
+    projects: &projects::Projects,
+    name: &Option<String>,
+    at: NaiveTime,
+    to: Option<NaiveTime>,
+    duration: Option<TimeDelta>,
+    date: Option<NaiveDate>,
+    ticket: &Option<String>,
+    project: &Option<String>,
+) -> Result<()> {
+    let date = date.unwrap_or_else(|| Local::now().date_naive());
+    let Some(start) = time::local_date_time(date, at) else {
+        println!("{at} does not exist on {date}.");
+        return Ok(());
+    };
+    let end = match (to, duration) {
+        (Some(to), _) => time::local_date_time(date, to),
+        (_, Some(duration)) => match start.checked_add_signed(duration) {
+            Some(end) => Some(end),
+            None => {
+                println!("The task can't last that long.");
+                return Ok(());
+            }
+        },
+        _ => None,
+    };
+    let Some(end) = end else {
+        println!("Please specify when the task ended with --to or --for.");
+        return Ok(());
+    };
+    if end <= start {
+        println!("The task has to end after it starts.");
+        return Ok(());
+    }
+
+    let project = match project {
+        Some(project) if projects.is_active(project) => project.to_owned(),
+        Some(project) if projects.exists(project) => {
+            return Err(Error::ArchivedProject(project.to_owned()))
+        }
+        Some(project) => return Err(Error::UnknownProject(project.to_owned())),
+        None => Task::set_project(projects, &None, None)?,
+    };
+    let name = name
+        .clone()
+        .or_else(|| input::input("Task name", None))
+        .ok_or(Error::NoTaskName)?;
+
+    let task = Task::new_completed(dir, name, ticket.clone(), project, start, end);
+    task.write_to_log_file()?;
+    TaskOut::current_task(&task);
+    Ok(())
+}
+
 fn main() {
     let cli = Cli::parse();
+    if let Err(e) = run(cli) {
+        eprintln!("{e}");
+        process::exit(e.exit_code());
+    }
+}
 
+fn run(cli: Cli) -> Result<()> {
     let data_dir = cli.data_dir;
+    let format = cli.format;
     //Putting this in as a safe gaurd so i don't over write my
     //time tracker data when testing.
     if is_debug() && data_dir.is_none() {
         panic!("Please specify which directory to save tracking files while devoloping. You can specify the directory using --data-dir")
     }
 
-    let dir = dir::Dir::new(data_dir);
-    let config = config::Config::new(&dir.config_file);
-    let projects = projects::Projects::new(&dir);
+    let dir = dir::Dir::new(data_dir)?;
+    let config = config::Config::new(&dir.config_file)?;
+    let projects = projects::Projects::new(&dir)?;
+    //Json and csv output is read by scripts, which should not be prompted
+    //or count as the user being active.
+    let stopped_idle_task = if format == Format::Table {
+        idle::check(&dir, &config, asks_about_idle_time(&cli.command))?
+    } else {
+        false
+    };
 
     match &cli.command {
         Commands::Projects(cmd) => match cmd {
-            Projects::List => ProjectsOut::list(&projects),
-            Projects::Add { name } => {
-                projects.add(name);
+            Projects::List => ProjectsOut::output(&projects, format),
+            Projects::Add { name, description } => {
+                projects.add(name, description.clone())?;
                 println!("Added project {name:?}");
             }
             Projects::Delete { name } => {
                 if projects.exists(name) {
                     if input::confirm(format!("Are you sure you wish to delete project {}", name)) {
-                        projects.delete(name);
+                        projects.delete(name)?;
                         println!("Project {name:?} deleted.")
                     }
                 } else {
                     println!("Project {name:?} does not exist.")
                 }
             }
+            Projects::Archive { name } | Projects::Unarchive { name } => {
+                let archived = matches!(cmd, Projects::Archive { .. });
+                if projects.exists(name) {
+                    projects.archive(name, archived)?;
+                    if archived {
+                        println!("Project {name:?} archived.")
+                    } else {
+                        println!("Project {name:?} unarchived.")
+                    }
+                } else {
+                    println!("Project {name:?} does not exist.")
+                }
+            }
+            Projects::Rename {
+                old,
+                new,
+                rewrite_logs,
+            } => {
+                if !projects.exists(old) {
+                    println!("Project {old:?} does not exist.");
+                    return Ok(());
+                }
+                if projects.exists(new) {
+                    println!("Project {new:?} already exists.");
+                    return Ok(());
+                }
+                projects.rename(old, new)?;
+                println!("Project {old:?} renamed to {new:?}.");
+                if *rewrite_logs {
+                    let renamed = log::rename_project(&dir, old, new)?;
+                    println!("Changed the project of {renamed} logged tasks.");
+                }
+            }
         },
         Commands::Task(cmd) => match cmd {
             TaskCmd::Start {
-                list, clip_board, ..
+                list,
+                mark_down,
+                mark_down_heading,
+                clip_board,
+                when,
             } => {
+                let Some(at) = resolve_when(when) else {
+                    return Ok(());
+                };
+
                 if *list {
-                    let tasks = Tasks::new(ChronoUnit::Month, &dir);
+                    let tasks = Tasks::new(ChronoUnit::Month, &dir)?;
                     if let Some(tasks) = tasks {
                         let task_names = tasks.get_names();
-                        let task_name = tui::menu("Select task:", &task_names);
-                        let task = tasks.get_latest_task_by_name(task_name);
+                        let Some(index) = tui::menu("Select task:", &task_names) else {
+                            return Ok(());
+                        };
+                        let task = tasks.get_latest_task_by_name(task_names[index]);
 
                         if let Some(task) = task {
-                            complete_current_task(&dir);
+                            //Archived projects don't take new time, the
+                            //project has to be restored first.
+                            if !projects.is_active(&task.project) {
+                                return Err(Error::ArchivedProject(task.project.clone()));
+                            }
+                            if !complete_current_task(&dir, at)? {
+                                return Ok(());
+                            }
 
-                            task.start();
-                            println!("{:?}", task);
-                        } else {
-                            todo!();
+                            let mut task = task.clone();
+                            task.start(at)?;
+                            TaskOut::current_task(&task);
                         }
                     } else {
                         println!("No existing tasks to select from.");
                     }
+                } else if let Some(file) = mark_down {
+                    start_from_mark_down(&dir, &projects, file, *mark_down_heading, at)?;
                 } else {
-                    complete_current_task(&dir);
+                    if !complete_current_task(&dir, at)? {
+                        return Ok(());
+                    }
 
-                    let task = task::Task::new(&dir, &projects, *clip_board);
-                    TaskOut::current_task(&task.expect("Could not create task"));
+                    let task = task::Task::new(&dir, &projects, *clip_board, at)?;
+                    TaskOut::current_task(&task);
                 }
             }
-            TaskCmd::Stop => {
-                let current_task = Task::from_current(&dir);
+            TaskCmd::Add {
+                name,
+                at,
+                to,
+                duration,
+                date,
+                ticket,
+                project,
+            } => add_task(
+                &dir, &projects, name, *at, *to, *duration, *date, ticket, project,
+            )?,
+            TaskCmd::Stop { when } => {
+                let Some(at) = resolve_when(when) else {
+                    return Ok(());
+                };
+
+                let current_task = Task::from_current(&dir)?;
                 if let Some(mut current_task) = current_task {
-                    current_task.complete();
+                    if at < current_task.start {
+                        println!(
+                            "Current task started at {}, it can't be stopped before that.",
+                            current_task.start.format("%H:%M")
+                        );
+                        return Ok(());
+                    }
+                    current_task.complete(at)?;
                     TaskOut::current_task(&current_task);
+                } else if let Some(paused_task) = Task::from_paused(&dir)? {
+                    dir.remove_paused_file()?;
+                    println!("Stopped paused task {:?}.", paused_task.name);
+                } else if !stopped_idle_task {
+                    return Err(Error::NoCurrentTask);
                 }
+            }
+            TaskCmd::Pause { when } => {
+                let Some(at) = resolve_when(when) else {
+                    return Ok(());
+                };
 
-                let _task = Task::from_current(&dir);
+                match Task::from_current(&dir)? {
+                    Some(task) if at < task.start => println!(
+                        "Current task started at {}, it can't be paused before that.",
+                        task.start.format("%H:%M")
+                    ),
+                    Some(mut task) => {
+                        task.pause(at)?;
+                        TaskOut::paused_task(&task);
+                    }
+                    None if stopped_idle_task => println!("The task was stopped, not paused."),
+                    None => return Err(Error::NoCurrentTask),
+                }
+            }
+            TaskCmd::Resume { when } => {
+                let Some(at) = resolve_when(when) else {
+                    return Ok(());
+                };
+
+                match Task::from_paused(&dir)? {
+                    Some(task) if task.end.is_some_and(|end| at < end) => println!(
+                        "Task was paused at {}, it can't be resumed before that.",
+                        task.end.unwrap().format("%H:%M")
+                    ),
+                    Some(mut task) => {
+                        if !complete_current_task(&dir, at)? {
+                            return Ok(());
+                        }
+                        task.resume(at)?;
+                        TaskOut::current_task(&task);
+                    }
+                    None => println!("There is no paused task to resume."),
+                }
             }
             TaskCmd::Current => {
-                let task = Task::from_current(&dir);
-                TaskOut::current_task(&task.expect("Could not create task"));
+                let task = Task::from_current(&dir)?;
+                match (&task, Task::from_paused(&dir)?) {
+                    (Some(task), _) => TaskOut::output(task, format),
+                    (None, Some(paused_task)) => TaskOut::output_paused(&paused_task, format),
+                    (None, None) => TaskOut::no_task(format),
+                }
+
+                let today = Local::now().date_naive();
+                if let (Format::Table, Some(target)) = (format, config.targets.daily(today)) {
+                    let logged = Tasks::new(ChronoUnit::Day, &dir)?;
+                    let time_spent = logged.as_ref().map_or(0, Tasks::time_spent)
+                        + task.as_ref().map_or(0, |t| t.time_spent_on(today));
+                    TargetOut::progress("today", time_spent, target);
+                }
             }
         },
         Commands::Open => {
-            open_file_in_editor(&config.editor, &dir.log_file);
+            open_file_in_editor(&config.editor, &dir.log_file)?;
         }
-        Commands::Logged => {
-            let tasks = Tasks::new(ChronoUnit::Week, &dir).unwrap();
-            tasks.output_task();
-            let time_spent = tasks.time_spent();
+        Commands::Ui => ui::run(&dir, &config, &projects)?,
+        Commands::Logged { week, month, year } => {
+            let (unit, period) = match (week, month, year) {
+                (true, _, _) => (ChronoUnit::Week, "this week"),
+                (_, true, _) => (ChronoUnit::Month, "this month"),
+                (_, _, true) => (ChronoUnit::Year, "this year"),
+                _ => (ChronoUnit::Day, "today"),
+            };
+            let per_day = !matches!(unit, ChronoUnit::Day);
+            let (from, to) = unit.range(Local::now().date_naive());
+            let tasks = Tasks::between(from, to, &dir)?;
+            let logged = tasks.as_ref().map_or(&[][..], |t| t.inner.as_slice());
+            let current_task = Task::from_current(&dir)?;
+            if format != Format::Table {
+                let tasks = logged.iter().chain(current_task.as_ref());
+                TaskOut::output_tasks(tasks, from, to, format);
+                return Ok(());
+            }
+
+            if logged.is_empty() {
+                println!("No tasks logged {period}.");
+            } else if per_day {
+                let days = Tasks::by_day(logged.iter().chain(current_task.as_ref()), from, to);
+                ReportOut::daily(&days);
+            } else {
+                TaskOut::output_tasks(logged, from, to, format);
+            }
+            let time_spent = tasks.as_ref().map_or(0, Tasks::time_spent);
 
             println!();
             println!();
             println!("Current Task:");
-            let current_task = Task::from_current(&dir).unwrap();
-            TaskOut::current_task(&current_task);
+            match &current_task {
+                Some(current_task) => TaskOut::current_task(current_task),
+                None => TaskOut::no_task(format),
+            }
 
-            let dur = Duration::new(time_spent + current_task.time_spent(), 0).unwrap();
-            println!("Time spent today: {}", pretty_duration(dur));
+            let current_time_spent = current_task
+                .as_ref()
+                .map_or(0, |t| t.time_spent_between(from, to));
+            let dur = Duration::new(time_spent + current_time_spent, 0).unwrap();
+            println!("Time spent {period}: {}", pretty_duration(dur));
+            if let Some(target) = config.targets.between(&unit, from, to) {
+                TargetOut::progress(period, time_spent + current_time_spent, target);
+            }
         }
+        Commands::Log(cmd) => match cmd {
+            LogCmd::Edit { period } => {
+                let (from, to) = period.dates();
+                log::edit(&dir, &projects, from, to)?;
+            }
+            LogCmd::Delete { period } => {
+                let (from, to) = period.dates();
+                log::delete(&dir, from, to)?;
+            }
+        },
+        Commands::Report { from, to, group_by } => {
+            let to = to.unwrap_or_else(|| Local::now().date_naive());
+            if *from > to {
+                println!("--from {from} is after --to {to}.");
+                return Ok(());
+            }
+
+            match Tasks::between(*from, to, &dir)? {
+                Some(tasks) => {
+                    match group_by {
+                        Some(group_by) => {
+                            let heading = format!("{:?}", group_by);
+                            let groups = tasks.group_by(*group_by, &config.rounding);
+                            let billing = matches!(group_by, GroupBy::Project).then_some(&projects);
+                            ReportOut::output_grouped(&heading, &groups, billing, format);
+                        }
+                        None => tasks.output_task(format, &config.rounding),
+                    }
+                    if format != Format::Table {
+                        return Ok(());
+                    }
+
+                    let dur = Duration::new(tasks.billed_time(&config.rounding), 0).unwrap();
+                    println!();
+                    println!("Time spent from {from} to {to}: {}", pretty_duration(dur));
+                    if let Some(rounding) = config.rounding.description() {
+                        println!("Time is {rounding}.");
+                    }
+                    let by_project = tasks.group_by(GroupBy::Project, &config.rounding);
+                    ReportOut::amounts(&by_project, &projects);
+                }
+                None if format == Format::Table => {
+                    println!("No tasks logged from {from} to {to}.")
+                }
+                None => out::records::<()>(format, &[]),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_complete_current_task_discards_paused_task() {
+        let (_tmp, dir) = testing::temp_dir();
+        let (start, end) = (testing::at(9), testing::at(10));
+        let row = testing::row(
+            "Write docs",
+            "",
+            "tlog",
+            "2026-10-01 09:00",
+            "2026-10-01 10:00",
+        );
+        fs::write(&dir.paused_file, testing::HEADER.to_owned() + &row).unwrap();
+
+        assert!(complete_current_task(&dir, end + TimeDelta::hours(1)).unwrap());
+        assert!(Task::from_paused(&dir).unwrap().is_none());
+        assert!(Task::from_current(&dir).unwrap().is_none());
+        //The paused segment was already logged, nothing more is written.
+        assert!(!Path::new(&dir.log_file_for(start.date_naive()).unwrap()).exists());
+    }
+
+    #[test]
+    fn test_asks_about_idle_time() {
+        let asks = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["tlog", "--data-dir", "x"], args].concat()).unwrap();
+            asks_about_idle_time(&cli.command)
+        };
+        assert!(asks(&["task", "stop"]));
+        assert!(asks(&["task", "pause"]));
+        assert!(asks(&["task", "start"]));
+        assert!(asks(&["logged"]));
+        assert!(!asks(&["task", "stop", "--ago", "10m"]));
+        assert!(!asks(&["task", "pause", "--at", "12:00"]));
+        assert!(!asks(&["task", "start", "--ago", "5m"]));
+        assert!(!asks(&["task", "resume", "--at", "13:00"]));
+    }
+
+    fn hm(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_add_task() {
+        let (_tmp, dir) = testing::temp_dir();
+        projects::Projects::new(&dir)
+            .unwrap()
+            .add("p1", None)
+            .unwrap();
+        let projects = projects::Projects::new(&dir).unwrap();
+        let add = |date, at, to, duration| {
+            let (name, ticket) = (Some("late".to_owned()), Some("T-1".to_owned()));
+            let project = Some("p1".to_owned());
+            add_task(
+                &dir,
+                &projects,
+                &name,
+                at,
+                to,
+                duration,
+                Some(date),
+                &ticket,
+                &project,
+            )
+        };
+
+        //Filed under the month it started in, even when that is not now.
+        add(
+            testing::date("2026-08-31"),
+            hm(23, 0),
+            None,
+            Some(TimeDelta::hours(2)),
+        )
+        .unwrap();
+        let tasks = Tasks::between(
+            testing::date("2026-08-31"),
+            testing::date("2026-08-31"),
+            &dir,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(tasks.inner.len(), 1);
+        let task = &tasks.inner[0];
+        assert_eq!((task.name.as_str(), task.project.as_str()), ("late", "p1"));
+        assert_eq!(task.ticket_number.as_deref(), Some("T-1"));
+        assert_eq!(task.start, testing::local("2026-08-31 23:00"));
+        assert_eq!(task.end, Some(testing::local("2026-09-01 01:00")));
+        assert!(Path::new(&dir.month_file("2026", "8")).exists());
+        assert!(!Path::new(&dir.month_file("2026", "9")).exists());
+
+        //Ends before it starts, or lasts longer than a date can hold.
+        let date = testing::date("2026-10-01");
+        add(date, hm(10, 0), Some(hm(9, 0)), None).unwrap();
+        add(date, hm(10, 0), None, Some(TimeDelta::max_value())).unwrap();
+        assert!(!Path::new(&dir.month_file("2026", "10")).exists());
+    }
+
+    #[test]
+    fn test_add_task_project() {
+        let (_tmp, dir) = testing::temp_dir();
+        projects::Projects::new(&dir)
+            .unwrap()
+            .add("old", None)
+            .unwrap();
+        projects::Projects::new(&dir)
+            .unwrap()
+            .archive("old", true)
+            .unwrap();
+        let projects = projects::Projects::new(&dir).unwrap();
+        let add = |project: &str| {
+            let name = Some("x".to_owned());
+            let project = Some(project.to_owned());
+            let date = Some(testing::date("2026-10-01"));
+            add_task(
+                &dir,
+                &projects,
+                &name,
+                hm(9, 0),
+                Some(hm(10, 0)),
+                None,
+                date,
+                &None,
+                &project,
+            )
+        };
+
+        assert!(matches!(add("old"), Err(Error::ArchivedProject(p)) if p == "old"));
+        assert!(matches!(add("new"), Err(Error::UnknownProject(p)) if p == "new"));
     }
 }
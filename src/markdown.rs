@@ -0,0 +1,78 @@
+const UNCHECKED_ITEM: &str = "- [ ]";
+
+fn is_heading(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+fn heading_title(line: &str) -> String {
+    line.trim().trim_start_matches('#').trim().to_owned()
+}
+
+fn unchecked_item(line: &str) -> Option<String> {
+    line.trim_start()
+        .strip_prefix(UNCHECKED_ITEM)
+        .map(|item| item.trim().to_owned())
+        .filter(|item| !item.is_empty())
+}
+
+///All headings in the markdown file, without the leading "#"s.
+pub fn headings(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .filter(|line| is_heading(line))
+        .map(heading_title)
+        .collect()
+}
+
+///All unchecked items (lines starting with "- [ ]") in the markdown file.
+pub fn unchecked_items(contents: &str) -> Vec<String> {
+    contents.lines().filter_map(unchecked_item).collect()
+}
+
+///Unchecked items found below the given heading, up until the next heading.
+pub fn unchecked_items_under(contents: &str, heading: &str) -> Vec<String> {
+    contents
+        .lines()
+        .skip_while(|line| !(is_heading(line) && heading_title(line) == heading))
+        .skip(1)
+        .take_while(|line| !is_heading(line))
+        .filter_map(unchecked_item)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = "# Monday
+- [ ] Write report
+- [x] Standup
+ - [ ] Review PR
+
+## Tuesday
+- [ ] Deploy
+";
+
+    #[test]
+    fn test_unchecked_items() {
+        assert_eq!(
+            unchecked_items(PLAN),
+            vec!["Write report", "Review PR", "Deploy"]
+        );
+    }
+
+    #[test]
+    fn test_unchecked_items_under() {
+        assert_eq!(
+            unchecked_items_under(PLAN, "Monday"),
+            vec!["Write report", "Review PR"]
+        );
+        assert_eq!(unchecked_items_under(PLAN, "Tuesday"), vec!["Deploy"]);
+        assert!(unchecked_items_under(PLAN, "Friday").is_empty());
+    }
+
+    #[test]
+    fn test_headings() {
+        assert_eq!(headings(PLAN), vec!["Monday", "Tuesday"]);
+    }
+}
@@ -1,7 +1,9 @@
 use std::path::Path;
 pub use std::process::Command;
 
-pub fn open_file_in_editor(command: &Option<String>, filename: &str) {
+use crate::error::Result;
+
+pub fn open_file_in_editor(command: &Option<String>, filename: &str) -> Result<()> {
     let command = match command {
         Some(command) => command,
         None => {
@@ -18,9 +20,7 @@ pub fn open_file_in_editor(command: &Option<String>, filename: &str) {
     println!("{:?}", filename);
     // Check if the file exists, create it if not
     if Path::new(filename).exists() {
-        let _status = Command::new(command)
-            .arg(filename)
-            .status()
-            .expect("Failed to open file with default editor");
+        let _status = Command::new(command).arg(filename).status()?;
     }
+    Ok(())
 }
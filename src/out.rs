@@ -1,2 +1,122 @@
 pub mod projects;
+pub mod report;
+pub mod target;
 pub mod task;
+
+use serde::Serialize;
+use std::io;
+
+///How listing commands print their output.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    ///Human readable boxes and tables.
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+///Print records as a JSON array or as CSV rows with a header. Does nothing
+///for `Format::Table`, callers print their own tables.
+pub fn records<T: Serialize>(format: Format, records: &[T]) {
+    let _ = write_records(format, records, io::stdout());
+}
+
+///Write records as a JSON array or as CSV rows with a header.
+pub fn write_records<T: Serialize>(
+    format: Format,
+    records: &[T],
+    mut out: impl io::Write,
+) -> io::Result<()> {
+    match format {
+        Format::Table => {}
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut out, records)?;
+            writeln!(out)?;
+        }
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(out);
+            records.iter().for_each(|r| {
+                let _ = wtr.serialize(r);
+            });
+            wtr.flush()?;
+        }
+    }
+    Ok(())
+}
+
+///Print a single record as a JSON object or as one CSV row with a header.
+pub fn record<T: Serialize>(format: Format, record: &T) {
+    match format {
+        Format::Json => {
+            println!("{}", serde_json::to_string_pretty(record).unwrap());
+        }
+        _ => records(format, std::slice::from_ref(record)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::out::task::TaskRecord;
+    use crate::testing::at;
+
+    fn task_record() -> TaskRecord<'static> {
+        let start = at(9);
+        TaskRecord {
+            name: "Write docs",
+            ticket_number: None,
+            project: "tlog",
+            start,
+            end: Some(start + chrono::Duration::minutes(90)),
+            time_spent: 90 * 60,
+        }
+    }
+
+    fn written(format: Format) -> String {
+        let mut buffer = Vec::new();
+        write_records(format, &[task_record()], &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_csv_records() {
+        let csv = written(Format::Csv);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("Name,TicketNumber,Project,Start,End,TimeSpent")
+        );
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("Write docs,,tlog,2026-10-01T09:00:00"));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn test_json_records() {
+        let json: serde_json::Value = serde_json::from_str(&written(Format::Json)).unwrap();
+        let record = &json.as_array().unwrap()[0];
+        let mut keys: Vec<_> = record.as_object().unwrap().keys().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "End",
+                "Name",
+                "Project",
+                "Start",
+                "TicketNumber",
+                "TimeSpent"
+            ]
+        );
+        assert_eq!(record["TicketNumber"], serde_json::Value::Null);
+        assert_eq!(record["TimeSpent"], 5400);
+    }
+
+    #[test]
+    fn test_table_writes_nothing() {
+        assert_eq!(written(Format::Table), "");
+    }
+}
@@ -1,9 +1,89 @@
-use crate::projects::Projects;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::out::{self, Format};
+use crate::projects::{Project, Projects};
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ProjectRecord<'a> {
+    name: &'a str,
+    description: Option<&'a str>,
+    archived: bool,
+    created: NaiveDate,
+}
 
 pub struct ProjectsOut;
 impl ProjectsOut {
     pub fn list(projects: &Projects) {
         println!("Available projects:");
-        projects.get().iter().for_each(|p| println!(" - {}", p));
+        Self::named(projects.active()).for_each(Self::project);
+    }
+
+    //Projects with an empty name can't be selected, so none of the formats
+    //list them.
+    fn named<'a>(projects: impl Iterator<Item = &'a Project>) -> impl Iterator<Item = &'a Project> {
+        projects.filter(|p| !p.name.is_empty())
+    }
+
+    fn records<'a>(projects: &'a Projects) -> Vec<ProjectRecord<'a>> {
+        Self::named(projects.get().iter())
+            .map(|p| ProjectRecord {
+                name: &p.name,
+                description: p.description.as_deref(),
+                archived: p.archived,
+                created: p.created,
+            })
+            .collect()
+    }
+
+    fn project(project: &Project) {
+        match &project.description {
+            Some(description) => println!(" - {}: {}", project.name, description),
+            None => println!(" - {}", project.name),
+        }
+    }
+
+    pub fn output(projects: &Projects, format: Format) {
+        match format {
+            Format::Table => {
+                Self::list(projects);
+                let mut archived = Self::named(projects.get().iter())
+                    .filter(|p| p.archived)
+                    .peekable();
+                if archived.peek().is_some() {
+                    println!("Archived projects:");
+                    archived.for_each(Self::project);
+                }
+            }
+            _ => out::records(format, &Self::records(projects)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use std::fs;
+
+    #[test]
+    fn test_records() {
+        let (_tmp, dir) = testing::temp_dir();
+        fs::write(
+            &dir.projects_file,
+            "[[project]]\nname = \"\"\ncreated = \"2026-10-01\"\n\n\
+             [[project]]\nname = \"tlog\"\ndescription = \"Time logs\"\n\
+             archived = true\ncreated = \"2026-10-02\"\n",
+        )
+        .unwrap();
+        let projects = Projects::new(&dir).unwrap();
+
+        let mut buffer = Vec::new();
+        out::write_records(Format::Csv, &ProjectsOut::records(&projects), &mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "Name,Description,Archived,Created\ntlog,Time logs,true,2026-10-02\n"
+        );
     }
 }
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::out::{self, task::pretty_duration, Format};
+use crate::projects::Projects;
+use crate::task::Task;
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct GroupRecord<'a> {
+    group: &'a str,
+    ///Time spent in seconds.
+    time_spent: i64,
+    percentage: f64,
+}
+
+///A group when grouping by project, with the amount owed for it.
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ProjectGroupRecord<'a> {
+    group: &'a str,
+    ///Time spent in seconds.
+    time_spent: i64,
+    percentage: f64,
+    client: Option<&'a str>,
+    rate: Option<f64>,
+    amount: Option<f64>,
+    currency: Option<&'a str>,
+}
+
+fn percentage(seconds: i64, total: i64) -> f64 {
+    if total > 0 {
+        seconds as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+pub struct ReportOut;
+impl ReportOut {
+    ///Print grouped durations. When grouping by project, pass the projects
+    ///so exported records include the amount owed.
+    pub fn output_grouped(
+        heading: &str,
+        groups: &[(String, i64)],
+        billing: Option<&Projects>,
+        format: Format,
+    ) {
+        let total: i64 = groups.iter().map(|(_, seconds)| seconds).sum();
+        let percentage = |seconds| (percentage(seconds, total) * 10.0).round() / 10.0;
+        match (format, billing) {
+            (Format::Table, _) => Self::grouped(heading, groups),
+            (_, Some(billing)) => {
+                let records: Vec<_> = groups
+                    .iter()
+                    .map(|(group, seconds)| {
+                        let project = billing.find(group);
+                        ProjectGroupRecord {
+                            group,
+                            time_spent: *seconds,
+                            percentage: percentage(*seconds),
+                            client: project.and_then(|p| p.client.as_deref()),
+                            rate: project.and_then(|p| p.rate),
+                            amount: project.and_then(|p| p.amount(*seconds)),
+                            currency: project.and_then(|p| p.currency.as_deref()),
+                        }
+                    })
+                    .collect();
+                out::records(format, &records);
+            }
+            _ => {
+                let records: Vec<_> = groups
+                    .iter()
+                    .map(|(group, seconds)| GroupRecord {
+                        group,
+                        time_spent: *seconds,
+                        percentage: percentage(*seconds),
+                    })
+                    .collect();
+                out::records(format, &records);
+            }
+        }
+    }
+
+    ///Print a table of grouped durations with the share of the total.
+    pub fn grouped(heading: &str, groups: &[(String, i64)]) {
+        let total: i64 = groups.iter().map(|(_, seconds)| seconds).sum();
+        let rows: Vec<(&str, String, String)> = groups
+            .iter()
+            .map(|(name, seconds)| {
+                (
+                    name.as_str(),
+                    pretty_duration(Duration::seconds(*seconds)),
+                    format!("{:.1}%", percentage(*seconds, total)),
+                )
+            })
+            .collect();
+        let total_duration = pretty_duration(Duration::seconds(total));
+
+        let name_width = rows
+            .iter()
+            .map(|r| r.0.chars().count())
+            .chain([heading.len(), "Total".len()])
+            .max()
+            .unwrap_or_default();
+        let time_width = rows
+            .iter()
+            .map(|r| r.1.len())
+            .chain([total_duration.len()])
+            .max()
+            .unwrap_or_default();
+
+        println!("{:<name_width$} | {:<time_width$} | %", heading, "Time");
+        println!("{}", "-".repeat(name_width + time_width + 10));
+        rows.iter().for_each(|(name, time, percentage)| {
+            println!(
+                "{:<name_width$} | {:<time_width$} | {}",
+                name, time, percentage
+            )
+        });
+        println!("{}", "-".repeat(name_width + time_width + 10));
+        println!(
+            "{:<name_width$} | {:<time_width$} | 100.0%",
+            "Total", total_duration
+        );
+    }
+
+    ///Print the amount owed for each billable project, with a total for
+    ///each currency. Prints nothing when no project is billable.
+    pub fn amounts(groups: &[(String, i64)], projects: &Projects) {
+        let rows: Vec<_> = groups
+            .iter()
+            .filter_map(|(project, seconds)| {
+                let details = projects.find(project)?;
+                let amount = details.amount(*seconds)?;
+                Some((project.as_str(), details, *seconds, amount))
+            })
+            .collect();
+        if rows.is_empty() {
+            return;
+        }
+
+        let mut totals: BTreeMap<&str, f64> = BTreeMap::new();
+        println!();
+        println!("Amounts owed:");
+        rows.iter().for_each(|(project, details, seconds, amount)| {
+            let currency = details.currency.as_deref().unwrap_or("");
+            let client = match &details.client {
+                Some(client) => format!(" ({})", client),
+                _ => "".to_owned(),
+            };
+            println!(
+                " - {}{}: {} at {} = {:.2} {}",
+                project,
+                client,
+                pretty_duration(Duration::seconds(*seconds)),
+                details.rate.unwrap_or_default(),
+                amount,
+                currency
+            );
+            *totals.entry(currency).or_default() += amount;
+        });
+        totals
+            .iter()
+            .for_each(|(currency, total)| println!("Total: {:.2} {}", total, currency));
+    }
+
+    ///Print the tasks of each day followed by the day's subtotal. Tasks
+    ///running past midnight only count the time spent on that day.
+    pub fn daily(days: &BTreeMap<NaiveDate, Vec<&Task>>) {
+        days.iter().for_each(|(day, tasks)| {
+            let subtotal: i64 = tasks.iter().map(|t| t.time_spent_on(*day)).sum();
+            println!("{}", day.format("%A %Y-%m-%d"));
+            tasks.iter().for_each(|t| {
+                let ticket_string = match &t.ticket_number {
+                    Some(ticket) => format!(" <{}>", ticket),
+                    _ => "".to_owned(),
+                };
+                let end = match t.end {
+                    Some(end) => end.format("%H:%M").to_string(),
+                    None => "now".to_owned(),
+                };
+                println!(
+                    "  {}-{} {}{} [{}] {}",
+                    t.start.format("%H:%M"),
+                    end,
+                    t.name,
+                    ticket_string,
+                    t.project,
+                    pretty_duration(Duration::seconds(t.time_spent_on(*day)))
+                );
+            });
+            println!(
+                "  Subtotal: {}",
+                pretty_duration(Duration::seconds(subtotal))
+            );
+            println!();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentage() {
+        let groups = [150, 60, 30];
+        let total = groups.iter().sum();
+        let percentages: Vec<f64> = groups.iter().map(|s| percentage(*s, total)).collect();
+        assert_eq!(percentages, vec![62.5, 25.0, 12.5]);
+        assert_eq!(percentage(0, 0), 0.0);
+    }
+}
@@ -0,0 +1,43 @@
+use chrono::Duration;
+
+use crate::out::task::pretty_duration;
+
+const BAR_WIDTH: usize = 20;
+
+pub struct TargetOut;
+impl TargetOut {
+    ///Print a progress bar of the time spent towards a target, followed by
+    ///the time remaining or the time over the target.
+    pub fn progress(period: &str, time_spent: i64, target: i64) {
+        println!("{}", Self::line(period, time_spent, target));
+    }
+
+    pub fn line(period: &str, time_spent: i64, target: i64) -> String {
+        let ratio = if target > 0 {
+            time_spent as f64 / target as f64
+        } else {
+            1.0
+        };
+        let filled = ((ratio.min(1.0) * BAR_WIDTH as f64).round()) as usize;
+        let remaining = target - time_spent;
+        let status = if remaining > 0 {
+            format!(
+                "{} remaining",
+                pretty_duration(Duration::seconds(remaining))
+            )
+        } else {
+            format!(
+                "target met, {} over",
+                pretty_duration(Duration::seconds(-remaining))
+            )
+        };
+
+        format!(
+            "Target {period}: [{}{}] {:.0}% of {}, {status}",
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            ratio * 100.0,
+            pretty_duration(Duration::seconds(target))
+        )
+    }
+}
@@ -1,5 +1,10 @@
-use chrono::{prelude::Local, Duration, TimeDelta};
+use chrono::{
+    prelude::{DateTime, Local},
+    Duration, NaiveDate, TimeDelta,
+};
+use serde::Serialize;
 
+use crate::out::{self, Format};
 use crate::task::Task;
 
 pub fn pretty_duration(duration: TimeDelta) -> String {
@@ -26,18 +31,97 @@ pub fn pretty_duration(duration: TimeDelta) -> String {
     format!("{} ({})", time, description)
 }
 
+///A task as printed by the json and csv output formats.
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TaskRecord<'a> {
+    pub name: &'a str,
+    pub ticket_number: Option<&'a str>,
+    pub project: &'a str,
+    pub start: DateTime<Local>,
+    pub end: Option<DateTime<Local>>,
+    ///Time spent in seconds, for the current task this is up until now.
+    pub time_spent: i64,
+}
+
+impl<'a> From<&'a Task<'a>> for TaskRecord<'a> {
+    fn from(task: &'a Task<'a>) -> Self {
+        TaskRecord {
+            name: &task.name,
+            ticket_number: task.ticket_number.as_deref(),
+            project: &task.project,
+            start: task.start,
+            end: task.end,
+            time_spent: task.time_spent(),
+        }
+    }
+}
+
 //todo: Remove this struct.
 pub struct TaskOut;
 impl TaskOut {
+    pub fn output(task: &Task, format: Format) {
+        match format {
+            Format::Table => Self::current_task(task),
+            _ => out::record(format, &TaskRecord::from(task)),
+        }
+    }
+
+    pub fn output_paused(task: &Task, format: Format) {
+        match format {
+            Format::Table => Self::paused_task(task),
+            _ => out::record(format, &TaskRecord::from(task)),
+        }
+    }
+
+    ///Shown when no task is being tracked.
+    pub fn no_task(format: Format) {
+        match format {
+            Format::Table => println!("No active task."),
+            Format::Json => println!("null"),
+            Format::Csv => {}
+        }
+    }
+
+    ///Print the tasks with the time they spent between `from` and `to`,
+    ///leaving out the part of tasks that ran past either end.
+    pub fn output_tasks<'a>(
+        tasks: impl IntoIterator<Item = &'a Task<'a>>,
+        from: NaiveDate,
+        to: NaiveDate,
+        format: Format,
+    ) {
+        let tasks = tasks.into_iter();
+        match format {
+            Format::Table => tasks.for_each(|task| {
+                let time_spent = task.time_spent_between(from, to);
+                Self::task_with_duration(task, Duration::seconds(time_spent))
+            }),
+            _ => {
+                let records: Vec<TaskRecord> = tasks
+                    .map(|task| TaskRecord {
+                        time_spent: task.time_spent_between(from, to),
+                        ..TaskRecord::from(task)
+                    })
+                    .collect();
+                out::records(format, &records);
+            }
+        }
+    }
+
     pub fn current_task(task: &Task) {
         let now = Local::now();
 
-        let duration = if !task.current && task.end.is_some() {
-            task.end.unwrap() - task.start
-        } else {
-            now - task.start
+        let duration = match task.end {
+            Some(end) if !task.current => end - task.start,
+            _ => now - task.start,
         };
+        Self::task_with_duration(task, duration);
+    }
 
+    ///Print the task box with the given time logged, for listings that only
+    ///count part of the task.
+    pub fn task_with_duration(task: &Task, duration: TimeDelta) {
         let ticket_string = match &task.ticket_number {
             Some(t) => format!(" <{}>", t),
             _ => "".to_owned(),
@@ -48,4 +132,17 @@ impl TaskOut {
         println!("* - Time Logged: {}", pretty_duration(duration));
         println!("*******************");
     }
+
+    pub fn paused_task(task: &Task) {
+        let ticket_string = match &task.ticket_number {
+            Some(t) => format!(" <{}>", t),
+            _ => "".to_owned(),
+        };
+        let paused_at = task.end.unwrap_or(task.start);
+        println!("*******************");
+        println!("* {}{} (Paused)", task.name, ticket_string);
+        println!("* - Project: {}", task.project);
+        println!("* - Paused since: {}", paused_at.format("%H:%M"));
+        println!("*******************");
+    }
 }
@@ -1,56 +1,235 @@
+use chrono::{prelude::Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::io;
+
 use crate::dir::Dir;
+use crate::error::Result;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Project {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
+    pub created: NaiveDate,
+    ///Whether time spent on the project is invoiced.
+    #[serde(default = "billable")]
+    pub billable: bool,
+    ///Amount per hour.
+    pub rate: Option<f64>,
+    pub currency: Option<String>,
+    pub client: Option<String>,
+}
+
+fn billable() -> bool {
+    true
+}
+
+impl Project {
+    pub fn new(name: &str, description: Option<String>) -> Self {
+        Self {
+            name: name.to_owned(),
+            description,
+            archived: false,
+            created: Local::now().date_naive(),
+            billable: true,
+            rate: None,
+            currency: None,
+            client: None,
+        }
+    }
+
+    ///Amount owed for the time spent, none when the project is not billable
+    ///or has no rate.
+    pub fn amount(&self, seconds: i64) -> Option<f64> {
+        let rate = self.rate.filter(|_| self.billable)?;
+        let amount = seconds as f64 / (60.0 * 60.0) * rate;
+        Some((amount * 100.0).round() / 100.0)
+    }
+}
+
+//The layout of projects.toml, a `[[project]]` table for each project.
+#[derive(Serialize, Deserialize, Default)]
+struct ProjectsFile {
+    #[serde(default, rename = "project")]
+    projects: Vec<Project>,
+}
 
 pub struct Projects<'a> {
     dir: &'a Dir,
-    inner: Vec<String>,
+    inner: Vec<Project>,
 }
 
 impl<'a> Projects<'a> {
-    pub fn get(&'a self) -> &'a Vec<String> {
+    pub fn get(&'a self) -> &'a [Project] {
         &self.inner
     }
 
-    pub fn new(dir: &'a Dir) -> Self {
-        let projects_string = dir.read_project_file();
-        let split = projects_string
+    pub fn new(dir: &'a Dir) -> Result<Self> {
+        let file: ProjectsFile = toml::from_str(&dir.read_project_file()?)?;
+        Ok(Self {
+            inner: file.projects,
+            dir,
+        })
+    }
+
+    ///The projects file contents for projects named in the old comma
+    ///separated format.
+    pub fn from_names(names: &str) -> Result<String> {
+        let projects = names
             .split(',')
-            .map(|s| s.trim().to_string())
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| Project::new(name, None))
             .collect();
-        Self { inner: split, dir }
+        Self::to_toml(projects)
     }
 
-    pub fn add(mut self, name: &str) {
+    fn to_toml(mut projects: Vec<Project>) -> Result<String> {
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+        let file = ProjectsFile { projects };
+        Ok(toml::to_string(&file).map_err(io::Error::other)?)
+    }
+
+    pub fn add(mut self, name: &str, description: Option<String>) -> Result<()> {
         let project_exists = self.exists(name);
 
         if project_exists {
             println!("Project already exists");
         } else {
-            self.inner.push(name.to_owned());
-            self.save();
+            self.inner.push(Project::new(name, description));
+            self.save()?;
         }
+        Ok(())
     }
 
-    fn save(&self) {
-        let mut data = self
-            .inner
-            .iter()
-            .filter(|p| !p.is_empty())
-            .map(|p| p.as_str())
-            .collect::<Vec<_>>();
-        data.sort();
-        let data = data.join(",");
-        let _ = Dir::write(&self.dir.projects_file, &data);
+    fn save(&self) -> Result<()> {
+        let data = Self::to_toml(self.inner.clone())?;
+        Dir::write(&self.dir.projects_file, &data)
     }
 
+    ///The project with the name, archived or not.
+    pub fn find(&self, name: &str) -> Option<&Project> {
+        self.inner.iter().find(|p| p.name == name)
+    }
+
+    ///Whether the project exists, archived or not.
     pub fn exists(&self, name: &str) -> bool {
-        self.inner.iter().position(|p| p == name).is_some()
+        self.inner.iter().any(|p| p.name == name)
+    }
+
+    ///Whether the project exists and can be used for new tasks.
+    pub fn is_active(&self, name: &str) -> bool {
+        self.inner.iter().any(|p| p.name == name && !p.archived)
+    }
+
+    ///Projects that are not archived.
+    pub fn active(&self) -> impl Iterator<Item = &Project> {
+        self.inner.iter().filter(|p| !p.archived)
     }
 
-    pub fn delete(mut self, name: &str) {
-        let pos = self.inner.iter().position(|p| p == name);
+    pub fn archive(mut self, name: &str, archived: bool) -> Result<()> {
+        if let Some(project) = self.inner.iter_mut().find(|p| p.name == name) {
+            project.archived = archived;
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    pub fn rename(mut self, old: &str, new: &str) -> Result<()> {
+        if let Some(project) = self.inner.iter_mut().find(|p| p.name == old) {
+            project.name = new.to_owned();
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    pub fn delete(mut self, name: &str) -> Result<()> {
+        let pos = self.inner.iter().position(|p| p.name == name);
         if let Some(pos) = pos {
             self.inner.remove(pos);
-            self.save();
+            self.save()?;
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use std::fs;
+
+    #[test]
+    fn test_migrate_comma_separated_file() {
+        let (_tmp, dir) = testing::temp_dir();
+        fs::write(format!("{}/projects", dir.time_tracker_dir), "p2, p1,").unwrap();
+
+        let projects = Projects::new(&dir).unwrap();
+        let names: Vec<_> = projects.get().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["p1", "p2"]);
+        assert!(!projects.get()[0].archived);
+        assert!(fs::read_to_string(&dir.projects_file)
+            .unwrap()
+            .contains("[[project]]"));
+
+        //The old file is kept aside so the migration only happens once.
+        fs::write(&dir.projects_file, "").unwrap();
+        assert!(Projects::new(&dir).unwrap().get().is_empty());
+    }
+
+    #[test]
+    fn test_archive() {
+        let (_tmp, dir) = testing::temp_dir();
+        Projects::new(&dir).unwrap().add("p1", None).unwrap();
+        Projects::new(&dir).unwrap().archive("p1", true).unwrap();
+
+        let projects = Projects::new(&dir).unwrap();
+        assert!(projects.exists("p1"));
+        assert!(!projects.is_active("p1"));
+        assert_eq!(projects.active().count(), 0);
+    }
+
+    #[test]
+    fn test_name_with_comma() {
+        let (_tmp, dir) = testing::temp_dir();
+
+        Projects::new(&dir)
+            .unwrap()
+            .add("Client, Inc", Some("Invoiced monthly".to_owned()))
+            .unwrap();
+        let projects = Projects::new(&dir).unwrap();
+        assert!(projects.exists("Client, Inc"));
+        assert!(!projects.exists("Client"));
+        assert_eq!(
+            projects.get()[0].description.as_deref(),
+            Some("Invoiced monthly")
+        );
+    }
+
+    #[test]
+    fn test_billing() {
+        let (_tmp, dir) = testing::temp_dir();
+        fs::write(
+            &dir.projects_file,
+            "[[project]]\nname = \"Client, Inc\"\ncreated = \"2026-10-01\"\n\
+             rate = 80\ncurrency = \"EUR\"\n\n\
+             [[project]]\nname = \"internal\"\ncreated = \"2026-10-01\"\n\
+             billable = false\nrate = 50\n",
+        )
+        .unwrap();
+
+        let projects = Projects::new(&dir).unwrap();
+        let client = projects.find("Client, Inc").unwrap();
+        assert!(client.billable);
+        assert_eq!(client.currency.as_deref(), Some("EUR"));
+        assert_eq!(client.amount(90 * 60), Some(120.0));
+        assert_eq!(client.amount(20 * 60), Some(26.67));
+        assert_eq!(projects.find("internal").unwrap().amount(60 * 60), None);
+
+        //Renaming keeps the billing details.
+        projects.rename("Client, Inc", "Client").unwrap();
+        let projects = Projects::new(&dir).unwrap();
+        assert_eq!(projects.find("Client").unwrap().rate, Some(80.0));
     }
 }
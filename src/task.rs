@@ -1,19 +1,24 @@
-use core::panic;
 use std::fs::OpenOptions;
 use std::path::Path;
 
 use chrono::prelude::{DateTime, Local};
+use chrono::{Duration, NaiveDate};
 use csv::{Reader, Writer, WriterBuilder};
 use serde::{Deserialize, Serialize};
 
 use crate::dir::Dir;
+use crate::error::{Error, Result};
 use crate::input;
 use crate::out::projects::ProjectsOut;
+use crate::time;
 
 use crate::projects::Projects;
 
 use arboard::Clipboard;
 
+//How many times to ask for a project before giving up.
+const PROJECT_ATTEMPTS: usize = 3;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct Task<'a> {
@@ -33,64 +38,100 @@ impl<'a> Task<'a> {
         dir: &'a Dir,
         projects: &Projects,
         default_name_from_clipboard: bool,
-    ) -> Option<Self> {
-        let last_task = Self::from_last(dir);
+        start: DateTime<Local>,
+    ) -> Result<Self> {
+        let last_task = Self::from_last(dir)?;
 
         let default = Self::default_name(
             default_name_from_clipboard,
             last_task.as_ref().map(|t| t.name.to_owned()),
-        );
-        let name = match input::input("Task name", default) {
-            Some(name) => name,
-            _ => panic!("Please enter task name!"),
-        };
+        )?;
+        let name = input::input("Task name", default).ok_or(Error::NoTaskName)?;
+
+        Self::create(dir, projects, name, last_task, start)
+    }
+
+    ///Create a task with a known name, only prompting for the ticket number
+    ///and project.
+    pub fn new_with_name(
+        dir: &'a Dir,
+        projects: &Projects,
+        name: String,
+        start: DateTime<Local>,
+    ) -> Result<Self> {
+        let last_task = Self::from_last(dir)?;
+        Self::create(dir, projects, name, last_task, start)
+    }
 
+    fn create(
+        dir: &'a Dir,
+        projects: &Projects,
+        name: String,
+        last_task: Option<Self>,
+        start: DateTime<Local>,
+    ) -> Result<Self> {
         let default = last_task.as_ref().and_then(|t| t.ticket_number.clone());
         let ticket_number = input::input("Ticket Number", default);
 
         let default = last_task.as_ref().map(|t| t.project.to_owned());
-        let project = Self::set_project(projects, &default);
+        let project = Self::set_project(projects, &default, None)?;
 
         let task = Self {
             name,
             ticket_number,
             project,
-            start: Local::now(),
+            start,
             end: None,
             dir: Some(dir),
             current: true,
         };
 
-        let mut wtr = Writer::from_path(&dir.current_file).unwrap();
-        let _ = wtr.serialize(&task);
+        let mut wtr = Writer::from_path(&dir.current_file)?;
+        wtr.serialize(&task)?;
+
+        Ok(task)
+    }
 
-        Some(task)
+    ///A task that has already been completed, used to log time after the
+    ///fact.
+    pub fn new_completed(
+        dir: &'a Dir,
+        name: String,
+        ticket_number: Option<String>,
+        project: String,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+    ) -> Self {
+        Self {
+            name,
+            ticket_number,
+            project,
+            start,
+            end: Some(end),
+            dir: Some(dir),
+            current: false,
+        }
     }
 
     fn default_name(
         default_name_from_clipboard: bool,
         last_task_name: Option<String>,
-    ) -> Option<String> {
-        let clipboard_value = Self::get_clipboard_name();
-
+    ) -> Result<Option<String>> {
         if default_name_from_clipboard {
-            if let Some(value) = clipboard_value {
-                Some(value)
-            } else {
-                last_task_name
+            if let Some(value) = Self::get_clipboard_name()? {
+                return Ok(Some(value));
             }
-        } else {
-            last_task_name
         }
+        Ok(last_task_name)
     }
 
-    fn get_clipboard_name() -> Option<String> {
-        let mut clipboard = Clipboard::new().unwrap();
-        let clipboard_contents = clipboard.get_text().unwrap();
+    fn get_clipboard_name() -> Result<Option<String>> {
+        let mut clipboard = Clipboard::new()?;
+        let clipboard_contents = clipboard.get_text()?;
         if clipboard_contents.contains('\n') {
-            None
+            Ok(None)
         } else {
-            Some(clipboard.get_text().unwrap())
+            Ok(Some(clipboard_contents))
         }
     }
 
@@ -98,95 +139,245 @@ impl<'a> Task<'a> {
         self.dir = Some(dir);
     }
 
-    pub fn start(&self) {
-        let mut wtr = Writer::from_path(&self.dir.unwrap().current_file).unwrap();
-        let _ = wtr.serialize(self);
+    ///Make this the current task, starting at the given time.
+    pub fn start(&mut self, at: DateTime<Local>) -> Result<()> {
+        self.start = at;
+        self.end = None;
+        self.current = true;
+        let mut wtr = Writer::from_path(&self.dir().current_file)?;
+        wtr.serialize(&*self)?;
+        Ok(())
     }
 
-    pub fn complete(&mut self) {
-        self.end = Some(Local::now());
-        self.write_last_file();
-        self.dir().remove_current_file();
-        self.write_to_log_file();
+    pub fn complete(&mut self, at: DateTime<Local>) -> Result<()> {
+        self.end = Some(at);
+        self.current = false;
+        self.write_last_file()?;
+        self.dir().remove_current_file()?;
+        self.write_to_log_file()
     }
 
     pub fn dir(&self) -> &Dir {
         self.dir.expect("Dir should exists")
     }
 
-    pub fn write_to_log_file(&self) {
-        let log_location = self.dir().get_log_file_location();
-        let path = Path::new(&log_location);
-        let include_headers = !path.exists();
-        let file = self.dir().get_log_file_location();
-        let file = OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(file)
-            .unwrap();
+    ///The log file the task belongs in, the month it was started in.
+    pub fn log_file(&self) -> Result<String> {
+        self.dir().log_file_for(self.start.date_naive())
+    }
+
+    pub fn write_to_log_file(&self) -> Result<()> {
+        let file = self.log_file()?;
+        let include_headers = !Path::new(&file).exists();
+        let file = OpenOptions::new().append(true).create(true).open(file)?;
 
         let mut wtr = WriterBuilder::new();
         let mut wtr = wtr.has_headers(include_headers).from_writer(file);
-        let _ = wtr.serialize(self);
-        let _ = wtr.flush();
+        wtr.serialize(self)?;
+        wtr.flush()?;
+        Ok(())
     }
 
-    pub fn write_last_file(&self) {
-        let mut wtr = Writer::from_path(&self.dir().last_file).unwrap();
+    ///Complete the current segment of the task and keep it so it can be
+    ///resumed with the same name, ticket and project.
+    pub fn pause(&mut self, at: DateTime<Local>) -> Result<()> {
+        self.complete(at)?;
+        let mut wtr = Writer::from_path(&self.dir().paused_file)?;
+        wtr.serialize(&*self)?;
+        Ok(())
+    }
 
-        let _ = wtr.serialize(self);
+    ///Start a new segment of a paused task.
+    pub fn resume(&mut self, at: DateTime<Local>) -> Result<()> {
+        self.dir().remove_paused_file()?;
+        self.start(at)
     }
 
-    pub fn from_current(dir: &'a Dir) -> Option<Self> {
-        if let Ok(mut rdr) = Reader::from_path(&dir.current_file) {
-            if let Some(Ok(Some(task))) = rdr.deserialize().next() {
-                let task = Task {
-                    dir: Some(dir),
-                    current: true,
-                    ..task
-                };
-                return Some(task);
-            }
-        }
-        None
+    pub fn write_last_file(&self) -> Result<()> {
+        let mut wtr = Writer::from_path(&self.dir().last_file)?;
+        wtr.serialize(self)?;
+        Ok(())
     }
 
-    fn from_last(dir: &'a Dir) -> Option<Self> {
-        if let Ok(mut rdr) = Reader::from_path(&dir.last_file) {
-            if let Some(Ok(Some(task))) = rdr.deserialize().next() {
-                let task = Task {
-                    dir: Some(dir),
-                    ..task
-                };
-                return Some(task);
-            }
+    //Read the single task stored in the current, paused or last file.
+    fn from_file(dir: &'a Dir, file: &str) -> Result<Option<Self>> {
+        if !Path::new(file).exists() {
+            return Ok(None);
         }
-        None
+
+        let mut rdr = Reader::from_path(file)?;
+        let task: Option<Task> = rdr.deserialize().next().transpose()?;
+        Ok(task.map(|task| Task {
+            dir: Some(dir),
+            ..task
+        }))
     }
 
-    fn set_project(projects: &Projects, default_value: &Option<String>) -> String {
-        let mut project = match input::input("Project", default_value.clone()) {
-            Some(project) => project,
-            _ => {
-                println!("Invalide project");
-                ProjectsOut::list(projects);
-                Self::set_project(projects, default_value)
+    pub fn from_current(dir: &'a Dir) -> Result<Option<Self>> {
+        let task = Self::from_file(dir, &dir.current_file)?;
+        Ok(task.map(|task| Task {
+            current: true,
+            ..task
+        }))
+    }
+
+    pub fn from_paused(dir: &'a Dir) -> Result<Option<Self>> {
+        Self::from_file(dir, &dir.paused_file)
+    }
+
+    fn from_last(dir: &'a Dir) -> Result<Option<Self>> {
+        Self::from_file(dir, &dir.last_file)
+    }
+
+    ///Prompt for the project. Archived projects are not accepted, except
+    ///for `keep` so a logged task can keep the project it has. Gives up
+    ///after a few invalid answers, for example when stdin is closed.
+    pub fn set_project(
+        projects: &Projects,
+        default_value: &Option<String>,
+        keep: Option<&str>,
+    ) -> Result<String> {
+        let mut project = None;
+        for _ in 0..PROJECT_ATTEMPTS {
+            project = input::input("Project", default_value.clone());
+            match &project {
+                Some(project) if projects.is_active(project) || keep == Some(project) => {
+                    return Ok(project.to_owned())
+                }
+                Some(project) => println!("Invalide project \"{}\"", project),
+                None => println!("Invalide project"),
             }
-        };
-        if !projects.exists(&project.to_string()) {
-            println!("Invalide project \"{}\"", project);
             ProjectsOut::list(projects);
-            project = Self::set_project(projects, default_value);
         }
-        project
+
+        match project {
+            Some(project) if projects.exists(&project) => Err(Error::ArchivedProject(project)),
+            Some(project) => Err(Error::UnknownProject(project)),
+            None => Err(Error::NoProject),
+        }
+    }
+
+    //When the task ended, now for the current task.
+    fn end_or_now(&self) -> Option<DateTime<Local>> {
+        match self.end {
+            Some(end) => Some(end),
+            None if self.current => Some(Local::now()),
+            None => None,
+        }
     }
 
     pub fn time_spent(&self) -> i64 {
-        if self.end.is_some() {
-            return (self.end.unwrap() - self.start).num_seconds();
-        } else if self.end.is_none() && self.current {
-            return (Local::now() - self.start).num_seconds();
+        self.end_or_now()
+            .map_or(0, |end| (end - self.start).num_seconds())
+    }
+
+    ///The part of the time spent that falls between the start of `from` and
+    ///the end of `to`, so tasks running past midnight are split across days.
+    pub fn time_spent_between(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        let Some(end) = self.end_or_now() else {
+            return 0;
+        };
+        let start = self.start.max(time::start_of_day(from));
+        let end = end.min(time::start_of_day(to + Duration::days(1)));
+        (end - start).num_seconds().max(0)
+    }
+
+    pub fn time_spent_on(&self, day: NaiveDate) -> i64 {
+        self.time_spent_between(day, day)
+    }
+
+    ///The days between `from` and `to` the task spent time on.
+    pub fn days(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let first = self.start.date_naive().max(from);
+        let last = self
+            .end_or_now()
+            .map_or(first, |end| end.date_naive())
+            .min(to);
+        first
+            .iter_days()
+            .take_while(|day| *day <= last)
+            .filter(|day| self.overlaps(*day, *day))
+            .collect()
+    }
+
+    ///Whether the task started or spent any time between the dates.
+    pub fn overlaps(&self, from: NaiveDate, to: NaiveDate) -> bool {
+        let start = self.start.date_naive();
+        (from <= start && start <= to) || self.time_spent_between(from, to) > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, at};
+    use std::fs;
+
+    fn task(dir: &Dir) -> Task<'_> {
+        Task {
+            name: "Write docs".to_owned(),
+            ticket_number: None,
+            project: "tlog".to_owned(),
+            start: Local::now(),
+            end: None,
+            dir: Some(dir),
+            current: false,
         }
-        0
+    }
+
+    #[test]
+    fn test_pause() {
+        let (_tmp, dir) = testing::temp_dir();
+        let mut task = task(&dir);
+        task.start(at(9)).unwrap();
+
+        task.pause(at(10)).unwrap();
+        assert!(Task::from_current(&dir).unwrap().is_none());
+        let paused = Task::from_paused(&dir).unwrap().unwrap();
+        assert_eq!(paused.start, at(9));
+        assert_eq!(paused.end, Some(at(10)));
+
+        let mut rdr = Reader::from_path(task.log_file().unwrap()).unwrap();
+        let logged: Vec<Task> = rdr.deserialize().map(|t| t.unwrap()).collect();
+        assert_eq!(logged.len(), 1);
+        assert_eq!(logged[0].name, "Write docs");
+        assert_eq!(logged[0].start, at(9));
+        assert_eq!(logged[0].end, Some(at(10)));
+    }
+
+    #[test]
+    fn test_resume() {
+        let (_tmp, dir) = testing::temp_dir();
+        let mut task = task(&dir);
+        task.start(at(9)).unwrap();
+        task.pause(at(10)).unwrap();
+
+        let mut paused = Task::from_paused(&dir).unwrap().unwrap();
+        paused.resume(at(11)).unwrap();
+        assert!(Task::from_paused(&dir).unwrap().is_none());
+        let current = Task::from_current(&dir).unwrap().unwrap();
+        assert_eq!(current.name, "Write docs");
+        assert_eq!(current.start, at(11));
+        assert_eq!(current.end, None);
+    }
+
+    #[test]
+    fn test_no_current_file() {
+        let (_tmp, dir) = testing::temp_dir();
+
+        assert!(Task::from_current(&dir).unwrap().is_none());
+        assert!(Task::from_paused(&dir).unwrap().is_none());
+        assert!(Task::from_last(&dir).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_empty_current_file() {
+        let (_tmp, dir) = testing::temp_dir();
+
+        fs::write(&dir.current_file, "").unwrap();
+        assert!(Task::from_current(&dir).unwrap().is_none());
+
+        fs::write(&dir.current_file, "Name,TicketNumber,Project,Start,End\n").unwrap();
+        assert!(Task::from_current(&dir).unwrap().is_none());
     }
 }
@@ -1,17 +1,36 @@
+use crate::config::{RoundPer, Rounding};
 use crate::dir::Dir;
-use crate::out::task::TaskOut;
+use crate::error::Result;
+use crate::out::{
+    self,
+    task::{TaskOut, TaskRecord},
+    Format,
+};
 use crate::Task;
 use chrono::Datelike;
-use chrono::{prelude::Local, Duration, NaiveDate, TimeDelta, Weekday};
-use csv::ReaderBuilder;
+use chrono::{prelude::Local, Duration, Months, NaiveDate};
+use csv::{Reader, ReaderBuilder};
 use std::fs::File;
+use std::hash::Hash;
+use std::io::Read;
 use std::path::Path;
 
 pub struct Tasks<'a> {
     pub inner: Vec<Task<'a>>,
+    ///First day of the period the tasks were loaded for.
+    pub from: NaiveDate,
+    ///Last day of the period the tasks were loaded for.
+    pub to: NaiveDate,
 }
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum GroupBy {
+    Project,
+    Ticket,
+    Name,
+}
 
 pub enum ChronoUnit {
     Year,
@@ -20,26 +39,93 @@ pub enum ChronoUnit {
     Day,
 }
 
+impl ChronoUnit {
+    ///The first and last day of the year, month, week or day containing
+    ///`date`.
+    pub fn range(&self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            ChronoUnit::Year => (
+                NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(date.year(), 12, 31).unwrap(),
+            ),
+            ChronoUnit::Month => {
+                let first = date.with_day(1).unwrap();
+                let next = first.checked_add_months(Months::new(1)).unwrap();
+                (first, next - Duration::days(1))
+            }
+            ChronoUnit::Week => Tasks::first_and_last_dates_of_week(date),
+            ChronoUnit::Day => (date, date),
+        }
+    }
+}
+
 impl Tasks<'_> {
-    pub fn new(unit: ChronoUnit, dir: &Dir) -> Option<Tasks> {
-        let mut tasks: Vec<Task> = vec![];
-
-        tasks = match unit {
-            ChronoUnit::Year => todo!(),
-            ChronoUnit::Month => Self::apply_month(dir, tasks, &dir.current_month),
-            ChronoUnit::Week => Self::apply_week(dir, tasks, &dir.current_month),
-            ChronoUnit::Day => Self::apply_day(dir, tasks, &dir.current_month),
-        };
+    pub fn new(unit: ChronoUnit, dir: &Dir) -> Result<Option<Tasks<'_>>> {
+        let (from, to) = unit.range(Local::now().date_naive());
+        Self::between(from, to, dir)
+    }
+
+    ///Load all tasks that spent time between `from` and `to` (both
+    ///inclusive), reading every month file the range touches.
+    pub fn between(from: NaiveDate, to: NaiveDate, dir: &Dir) -> Result<Option<Tasks<'_>>> {
+        let tasks = Self::apply_range(dir, vec![], from, to)?;
+        Ok(Self::from_vec(tasks, from, to))
+    }
 
+    fn from_vec(mut tasks: Vec<Task<'_>>, from: NaiveDate, to: NaiveDate) -> Option<Tasks<'_>> {
         if !tasks.is_empty() {
             //Make latest tasks at the top of the vec.
             tasks.reverse();
-            Some(Tasks { inner: tasks })
+            Some(Tasks {
+                inner: tasks,
+                from,
+                to,
+            })
         } else {
             None
         }
     }
 
+    //Every (year, month) pair between the two dates, in order.
+    pub fn months_between(from: NaiveDate, to: NaiveDate) -> Vec<(i32, u32)> {
+        let mut months = vec![];
+        let (mut year, mut month) = (from.year(), from.month());
+        while (year, month) <= (to.year(), to.month()) {
+            months.push((year, month));
+            if month == 12 {
+                year += 1;
+                month = 1;
+            } else {
+                month += 1;
+            }
+        }
+        months
+    }
+
+    pub fn apply_range<'a>(
+        dir: &'a Dir,
+        mut tasks: Vec<Task<'a>>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<Task<'a>>> {
+        //Tasks are filed under the month they started in, so one running past
+        //midnight into `from` can be in the previous month's file. Older logs
+        //filed tasks under the month they completed in, so read the month
+        //after `to` as well.
+        let first = from - Duration::days(1);
+        let last = to + Duration::days(1);
+        for (year, month) in Self::months_between(first, last) {
+            let month_tasks =
+                Self::apply_month(dir, vec![], &year.to_string(), &month.to_string())?;
+            tasks.extend(
+                month_tasks
+                    .into_iter()
+                    .filter(|task| task.overlaps(from, to)),
+            );
+        }
+        Ok(tasks)
+    }
+
     // Function to get the first and last dates of the week
     fn first_and_last_dates_of_week(date: NaiveDate) -> (NaiveDate, NaiveDate) {
         let weekday = date.weekday();
@@ -48,102 +134,136 @@ impl Tasks<'_> {
         (first_date, last_date)
     }
 
-    fn apply_week<'a>(dir: &'a Dir, mut tasks: Vec<Task<'a>>, month: &'a str) -> Vec<Task<'a>> {
-        // Get today's date
-        let today = Local::today().naive_local();
-
-        // Get the first and last dates of the week
-        let (first_date, last_date) = Self::first_and_last_dates_of_week(today);
-
-        let month_path = dir.month_file(&dir.current_year, month);
+    pub fn apply_month<'a>(
+        dir: &'a Dir,
+        mut tasks: Vec<Task<'a>>,
+        year: &str,
+        month: &str,
+    ) -> Result<Vec<Task<'a>>> {
+        //Read task fomr month file
+        let month_path = dir.month_file(year, month);
         let path = Path::new(&month_path);
         if path.exists() {
-            let file = File::open(path).unwrap();
+            let file = File::open(path)?;
 
             let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(file);
+            tasks.extend(Self::read_tasks(dir, &mut rdr)?);
+        }
+        Ok(tasks)
+    }
 
-            //Gather them into a vec.
-            for result in rdr.deserialize() {
-                let mut task: Task = result.unwrap();
-                if first_date < task.start.date().naive_local()
-                    && last_date >= task.start.date().naive_local()
-                {
-                    task.set_dir(dir);
-                    task.current = false;
-                    tasks.push(task);
-                }
-            }
+    ///Read the logged tasks of a month file.
+    pub fn read_tasks<'a, R: Read>(dir: &'a Dir, rdr: &mut Reader<R>) -> Result<Vec<Task<'a>>> {
+        let mut tasks = vec![];
+        for result in rdr.deserialize() {
+            let mut task: Task = result?;
+            task.set_dir(dir);
+            task.current = false;
+            tasks.push(task);
         }
+        Ok(tasks)
+    }
+
+    ///Time spent within the period, leaving out the part of tasks that ran
+    ///past either end of it.
+    pub fn time_spent(&self) -> i64 {
+        self.inner
+            .iter()
+            .map(|t| t.time_spent_between(self.from, self.to))
+            .sum()
+    }
 
-        tasks
+    ///Time spent within the period, rounded up for billing.
+    pub fn billed_time(&self, rounding: &Rounding) -> i64 {
+        self.sum_by(rounding, |_| ()).into_values().sum()
     }
 
-    fn apply_day<'a>(dir: &'a Dir, mut tasks: Vec<Task<'a>>, month: &'a str) -> Vec<Task<'a>> {
-        //Read task fomr current month file
-        let month_path = dir.month_file(&dir.current_year, month);
-        let path = Path::new(&month_path);
-        if path.exists() {
-            let file = File::open(path).unwrap();
+    ///Sum the time spent for each project, ticket or task name, rounded up
+    ///for billing. Groups are sorted by time spent, largest first.
+    pub fn group_by(&self, group_by: GroupBy, rounding: &Rounding) -> Vec<(String, i64)> {
+        let groups = self.sum_by(rounding, |task| match group_by {
+            GroupBy::Project => task.project.clone(),
+            GroupBy::Ticket => task
+                .ticket_number
+                .clone()
+                .unwrap_or_else(|| "(no ticket)".to_owned()),
+            GroupBy::Name => task.name.clone(),
+        });
 
-            let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(file);
+        let mut groups: Vec<_> = groups.into_iter().collect();
+        groups.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        groups
+    }
 
-            //Gather them into a vec.
-            for result in rdr.deserialize() {
-                let today = Local::today().naive_local();
-
-                let mut task: Task = result.unwrap();
-                if today == task.start.date().naive_local()
-                    || today == task.end.unwrap().date().naive_local()
-                {
-                    task.set_dir(dir);
-                    task.current = false;
-                    tasks.push(task);
+    //Time spent within the period for each key. When rounding per day, the
+    //time of each key is rounded for every day separately.
+    fn sum_by<K: Eq + Hash>(
+        &self,
+        rounding: &Rounding,
+        key: impl Fn(&Task) -> K,
+    ) -> HashMap<K, i64> {
+        let mut totals: HashMap<K, i64> = HashMap::new();
+        match rounding.per {
+            RoundPer::Entry => {
+                for task in &self.inner {
+                    let seconds = task.time_spent_between(self.from, self.to);
+                    *totals.entry(key(task)).or_default() += rounding.round(seconds);
+                }
+            }
+            RoundPer::Day => {
+                let mut days: HashMap<(K, NaiveDate), i64> = HashMap::new();
+                for task in &self.inner {
+                    for day in task.days(self.from, self.to) {
+                        *days.entry((key(task), day)).or_default() += task.time_spent_on(day);
+                    }
+                }
+                for ((key, _), seconds) in days {
+                    *totals.entry(key).or_default() += rounding.round(seconds);
                 }
             }
         }
-        tasks
+        totals
     }
 
-    pub fn apply_month<'a>(
-        dir: &'a Dir,
-        mut tasks: Vec<Task<'a>>,
-        month: &'a str,
-    ) -> Vec<Task<'a>> {
-        //Read task fomr current month file
-        let month_path = dir.month_file(&dir.current_year, month);
-        let path = Path::new(&month_path);
-        if path.exists() {
-            let file = File::open(path).unwrap();
-
-            let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(file);
-
-            //Gather them into a vec.
-            for result in rdr.deserialize() {
-                let mut task: Task = result.unwrap();
-                task.set_dir(dir);
-                task.current = false;
-                tasks.push(task);
+    ///Group tasks by the days between `from` and `to` they spent time on, in
+    ///date order. A task running past midnight is listed on each day. Tasks
+    ///within a day are sorted by start time.
+    pub fn by_day<'b, 'c>(
+        tasks: impl IntoIterator<Item = &'b Task<'c>>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> BTreeMap<NaiveDate, Vec<&'b Task<'c>>> {
+        let mut days: BTreeMap<NaiveDate, Vec<&Task>> = BTreeMap::new();
+        for task in tasks {
+            for day in task.days(from, to) {
+                days.entry(day).or_default().push(task);
             }
         }
-        tasks
+        days.values_mut()
+            .for_each(|tasks| tasks.sort_by_key(|t| t.start));
+        days
     }
 
-    pub fn time_spent(&self) -> i64 {
-        self.inner
-            .iter()
-            .map(|t| {
-                if t.end.is_some() {
-                    return (t.end.unwrap() - t.start).num_seconds();
-                }
-                0
-            })
-            .sum()
-    }
-
-    pub fn output_task(&self) {
-        self.inner.iter().for_each(|t| {
-            TaskOut::current_task(t);
-        });
+    ///Print the tasks with their time within the period, rounded up when
+    ///rounding per entry.
+    pub fn output_task(&self, format: Format, rounding: &Rounding) {
+        let time_spent = |task: &Task| rounding.entry(task.time_spent_between(self.from, self.to));
+        match format {
+            Format::Table => self.inner.iter().for_each(|task| {
+                TaskOut::task_with_duration(task, Duration::seconds(time_spent(task)))
+            }),
+            _ => {
+                let records: Vec<TaskRecord> = self
+                    .inner
+                    .iter()
+                    .map(|task| TaskRecord {
+                        time_spent: time_spent(task),
+                        ..TaskRecord::from(task)
+                    })
+                    .collect();
+                out::records(format, &records);
+            }
+        }
     }
 
     pub fn get_names(&self) -> Vec<&str> {
@@ -151,7 +271,223 @@ impl Tasks<'_> {
         unique_names.into_iter().collect()
     }
 
-    pub fn get_latest_task_by_name(&self, name: &str) -> Option<&Task> {
+    pub fn get_latest_task_by_name(&self, name: &str) -> Option<&Task<'_>> {
         self.inner.iter().find(|t| t.name == name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, date, HEADER};
+    use std::fs;
+
+    //A month log with a task from 12:00 to 13:00 for each name and day.
+    fn log_file(dir: &Dir, year: &str, month: &str, rows: &[(&str, &str)]) {
+        let rows: Vec<String> = rows
+            .iter()
+            .map(|(name, day)| row(name, &format!("{day} 12:00"), &format!("{day} 13:00")))
+            .collect();
+        testing::write_log(dir, year, month, &rows);
+    }
+
+    fn names(tasks: Vec<Task>) -> Vec<String> {
+        tasks.into_iter().map(|t| t.name).collect()
+    }
+
+    #[test]
+    fn test_first_and_last_dates_of_week() {
+        let (first, last) = Tasks::first_and_last_dates_of_week(date("2026-10-02"));
+        assert_eq!(first, date("2026-09-28"));
+        assert_eq!(last, date("2026-10-04"));
+
+        let (first, last) = Tasks::first_and_last_dates_of_week(date("2026-09-28"));
+        assert_eq!(first, date("2026-09-28"));
+        assert_eq!(last, date("2026-10-04"));
+    }
+
+    #[test]
+    fn test_week_across_months() {
+        let (_tmp, dir) = testing::temp_dir();
+        log_file(
+            &dir,
+            "2026",
+            "9",
+            &[("sunday", "2026-09-27"), ("monday", "2026-09-28")],
+        );
+        log_file(
+            &dir,
+            "2026",
+            "10",
+            &[("friday", "2026-10-02"), ("next", "2026-10-05")],
+        );
+
+        let (from, to) = ChronoUnit::Week.range(date("2026-10-02"));
+        let tasks = Tasks::apply_range(&dir, vec![], from, to).unwrap();
+        assert_eq!(names(tasks), vec!["monday", "friday"]);
+    }
+
+    #[test]
+    fn test_week_across_years() {
+        let (_tmp, dir) = testing::temp_dir();
+        log_file(
+            &dir,
+            "2026",
+            "12",
+            &[("before", "2026-12-27"), ("monday", "2026-12-28")],
+        );
+        log_file(
+            &dir,
+            "2027",
+            "1",
+            &[("friday", "2027-01-01"), ("sunday", "2027-01-03")],
+        );
+
+        let (from, to) = ChronoUnit::Week.range(date("2027-01-01"));
+        let tasks = Tasks::apply_range(&dir, vec![], from, to).unwrap();
+        assert_eq!(names(tasks), vec!["monday", "friday", "sunday"]);
+    }
+
+    #[test]
+    fn test_year() {
+        let (_tmp, dir) = testing::temp_dir();
+        log_file(&dir, "2025", "12", &[("last year", "2025-12-31")]);
+        log_file(&dir, "2026", "1", &[("january", "2026-01-01")]);
+        log_file(&dir, "2026", "6", &[("june", "2026-06-15")]);
+        log_file(&dir, "2026", "12", &[("december", "2026-12-31")]);
+        log_file(&dir, "2027", "1", &[("next year", "2027-01-01")]);
+
+        let (from, to) = ChronoUnit::Year.range(date("2026-06-15"));
+        assert_eq!((from, to), (date("2026-01-01"), date("2026-12-31")));
+        let tasks = Tasks::apply_range(&dir, vec![], from, to).unwrap();
+        assert_eq!(names(tasks), vec!["january", "june", "december"]);
+    }
+
+    //A row for a task of project "p" between two local date times.
+    fn row(name: &str, start: &str, end: &str) -> String {
+        ticket_row(name, "", start, end)
+    }
+
+    fn ticket_row(name: &str, ticket: &str, start: &str, end: &str) -> String {
+        testing::row(name, ticket, "p", start, end)
+    }
+
+    #[test]
+    fn test_task_spanning_midnight() {
+        let (_tmp, dir) = testing::temp_dir();
+        let late = row("late", "2026-08-31 23:00", "2026-09-01 01:00");
+        testing::write_log(&dir, "2026", "8", &[late]);
+
+        let aug = Tasks::between(date("2026-08-01"), date("2026-08-31"), &dir)
+            .unwrap()
+            .unwrap();
+        assert_eq!(aug.time_spent(), 60 * 60);
+
+        let sep = Tasks::between(date("2026-09-01"), date("2026-09-30"), &dir)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sep.time_spent(), 60 * 60);
+
+        let days = Tasks::by_day(&aug.inner, date("2026-08-30"), date("2026-09-02"));
+        assert_eq!(
+            days.keys().copied().collect::<Vec<_>>(),
+            vec![date("2026-08-31"), date("2026-09-01")]
+        );
+        assert!(Tasks::between(date("2026-09-02"), date("2026-09-02"), &dir)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_rounding() {
+        let (_tmp, dir) = testing::temp_dir();
+        log_file(
+            &dir,
+            "2026",
+            "10",
+            &[
+                ("a", "2026-10-01"),
+                ("b", "2026-10-01"),
+                ("c", "2026-10-02"),
+            ],
+        );
+        let tasks = Tasks::between(date("2026-10-01"), date("2026-10-31"), &dir)
+            .unwrap()
+            .unwrap();
+
+        let mut rounding = Rounding {
+            minutes: Some(45),
+            per: RoundPer::Entry,
+        };
+        assert_eq!(tasks.billed_time(&Rounding::default()), 3 * 60 * 60);
+        assert_eq!(tasks.billed_time(&rounding), 3 * 90 * 60);
+
+        rounding.per = RoundPer::Day;
+        assert_eq!(tasks.billed_time(&rounding), (135 + 90) * 60);
+        assert_eq!(
+            tasks.group_by(GroupBy::Project, &rounding),
+            vec![("p".to_owned(), (135 + 90) * 60)]
+        );
+    }
+
+    #[test]
+    fn test_group_by() {
+        let (_tmp, dir) = testing::temp_dir();
+        let rows = [
+            ticket_row("a", "T-1", "2026-10-01 10:00", "2026-10-01 12:00"),
+            ticket_row("c", "T-2", "2026-10-01 13:00", "2026-10-01 13:30"),
+            ticket_row("a", "", "2026-10-01 14:00", "2026-10-01 15:00"),
+            ticket_row("b", "T-1", "2026-10-01 15:00", "2026-10-01 15:30"),
+        ];
+        testing::write_log(&dir, "2026", "10", &rows);
+        let tasks = Tasks::between(date("2026-10-01"), date("2026-10-31"), &dir)
+            .unwrap()
+            .unwrap();
+        let rounding = Rounding::default();
+
+        let groups = |by| -> Vec<(String, i64)> { tasks.group_by(by, &rounding) };
+        let owned = |groups: &[(&str, i64)]| -> Vec<(String, i64)> {
+            groups
+                .iter()
+                .map(|(group, minutes)| (group.to_string(), minutes * 60))
+                .collect()
+        };
+        assert_eq!(
+            groups(GroupBy::Ticket),
+            owned(&[("T-1", 150), ("(no ticket)", 60), ("T-2", 30)])
+        );
+        //Groups with the same time are sorted by name.
+        assert_eq!(
+            groups(GroupBy::Name),
+            owned(&[("a", 180), ("b", 30), ("c", 30)])
+        );
+        assert_eq!(groups(GroupBy::Project), owned(&[("p", 240)]));
+    }
+
+    #[test]
+    fn test_empty_data_dir() {
+        let (_tmp, dir) = testing::temp_dir();
+
+        for unit in [
+            ChronoUnit::Year,
+            ChronoUnit::Month,
+            ChronoUnit::Week,
+            ChronoUnit::Day,
+        ] {
+            assert!(Tasks::new(unit, &dir).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn test_empty_month_files() {
+        let (_tmp, dir) = testing::temp_dir();
+        let month_file = dir.log_file_for(Local::now().date_naive()).unwrap();
+
+        fs::write(&month_file, "").unwrap();
+        assert!(Tasks::new(ChronoUnit::Month, &dir).unwrap().is_none());
+
+        fs::write(&month_file, HEADER).unwrap();
+        assert!(Tasks::new(ChronoUnit::Month, &dir).unwrap().is_none());
+        assert!(Tasks::new(ChronoUnit::Day, &dir).unwrap().is_none());
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::{
+    prelude::{DateTime, Local},
+    NaiveDate,
+};
+use std::fs;
+use tempfile::TempDir;
+
+use crate::dir::Dir;
+use crate::time;
+
+pub const HEADER: &str = "Name,TicketNumber,Project,Start,End\n";
+
+///A data directory inside a temporary directory, which is removed when the
+///returned `TempDir` is dropped.
+pub fn temp_dir() -> (TempDir, Dir) {
+    let tmp = TempDir::new().unwrap();
+    let dir = Dir::new(Some(tmp.path().to_str().unwrap().to_owned())).unwrap();
+    (tmp, dir)
+}
+
+pub fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+///A local date time such as "2026-10-01 09:00".
+pub fn local(date_time: &str) -> DateTime<Local> {
+    time::parse_date_time(date_time).unwrap()
+}
+
+///The local time on 2026-10-01 at the hour.
+pub fn at(hour: u32) -> DateTime<Local> {
+    local(&format!("2026-10-01 {hour}:00"))
+}
+
+///A log row for a task between two local date times.
+pub fn row(name: &str, ticket: &str, project: &str, start: &str, end: &str) -> String {
+    let (start, end) = (local(start).to_rfc3339(), local(end).to_rfc3339());
+    format!("{name},{ticket},{project},{start},{end}\n")
+}
+
+///Write a month log file with a header and the rows.
+pub fn write_log(dir: &Dir, year: &str, month: &str, rows: &[String]) {
+    fs::create_dir_all(format!("{}/{}", dir.time_tracker_dir, year)).unwrap();
+    fs::write(
+        dir.month_file(year, month),
+        HEADER.to_owned() + &rows.concat(),
+    )
+    .unwrap();
+}
@@ -0,0 +1,205 @@
+use chrono::{
+    prelude::{DateTime, Local},
+    NaiveDate, NaiveTime, TimeDelta, TimeZone,
+};
+use std::fmt::Display;
+
+///Parse a time of day such as "9:30", "09:30" or "14:05:30".
+pub fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+        .map_err(|_| format!("Invalid time {value:?}, expected HH:MM"))
+}
+
+///Parse a duration such as "45m", "2h", "1h30m" or "90s".
+pub fn parse_duration(value: &str) -> Result<TimeDelta, String> {
+    let invalid = || format!("Invalid duration {value:?}, expected something like 1h30m");
+    let mut seconds: i64 = 0;
+    let mut number = String::new();
+
+    for c in value.trim().chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let amount: i64 = number.parse().map_err(|_| invalid())?;
+        let unit = match c {
+            'h' => 60 * 60,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        seconds = amount
+            .checked_mul(unit)
+            .and_then(|amount| seconds.checked_add(amount))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+
+    if !number.is_empty() || seconds == 0 {
+        return Err(invalid());
+    }
+    TimeDelta::try_seconds(seconds).ok_or_else(invalid)
+}
+
+///Parse a date and time such as "2026-10-02 09:30".
+pub fn parse_date_time(value: &str) -> Result<DateTime<Local>, String> {
+    let invalid = || format!("Invalid date and time {value:?}, expected YYYY-MM-DD HH:MM");
+    let (date, time) = value.trim().split_once(' ').ok_or_else(invalid)?;
+    let date: NaiveDate = date.parse().map_err(|_| invalid())?;
+    let time = parse_time(time.trim()).map_err(|_| invalid())?;
+    local_date_time(date, time).ok_or_else(invalid)
+}
+
+///The local date time for a date and time of day. When the time happens
+///twice because of daylight saving the earliest one is used.
+pub fn local_date_time(date: NaiveDate, time: NaiveTime) -> Option<DateTime<Local>> {
+    Local.from_local_datetime(&date.and_time(time)).earliest()
+}
+
+///The first moment of a local day. Falls back to the earliest time that
+///exists when midnight is skipped by daylight saving.
+pub fn start_of_day(date: NaiveDate) -> DateTime<Local> {
+    (0..24)
+        .find_map(|hour| local_date_time(date, NaiveTime::from_hms_opt(hour, 0, 0)?))
+        .expect("A day should have at least one valid hour")
+}
+
+///When a task started or stopped, either at a time of day today, a duration
+///ago or now when neither is given.
+pub fn resolve(at: Option<NaiveTime>, ago: Option<TimeDelta>) -> Result<DateTime<Local>, String> {
+    resolve_from(Local::now(), at, ago)
+}
+
+//`resolve` relative to `now`, in the time zone of `now`.
+fn resolve_from<Tz: TimeZone>(
+    now: DateTime<Tz>,
+    at: Option<NaiveTime>,
+    ago: Option<TimeDelta>,
+) -> Result<DateTime<Tz>, String>
+where
+    Tz::Offset: Display,
+{
+    let time = match (at, ago) {
+        (Some(at), _) => now
+            .timezone()
+            .from_local_datetime(&now.date_naive().and_time(at))
+            .earliest()
+            .ok_or_else(|| format!("{at} does not exist today"))?,
+        (_, Some(ago)) => now
+            .clone()
+            .checked_sub_signed(ago)
+            .ok_or_else(|| "--ago is too long".to_owned())?,
+        _ => now.clone(),
+    };
+
+    if time > now {
+        Err(format!("{} is in the future", time.format("%H:%M")))
+    } else {
+        Ok(time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{LocalResult, NaiveDateTime, Timelike, Utc};
+
+    //UTC, except that 02:00 to 03:00 is skipped every day like on the day
+    //daylight saving starts.
+    #[derive(Clone, Copy, Debug)]
+    struct Gap;
+
+    impl TimeZone for Gap {
+        type Offset = Utc;
+
+        fn from_offset(_: &Utc) -> Self {
+            Gap
+        }
+
+        fn offset_from_local_date(&self, _: &NaiveDate) -> LocalResult<Utc> {
+            LocalResult::Single(Utc)
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<Utc> {
+            if local.hour() == 2 {
+                LocalResult::None
+            } else {
+                LocalResult::Single(Utc)
+            }
+        }
+
+        fn offset_from_utc_date(&self, _: &NaiveDate) -> Utc {
+            Utc
+        }
+
+        fn offset_from_utc_datetime(&self, _: &NaiveDateTime) -> Utc {
+            Utc
+        }
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let now = Gap.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+        assert_eq!(resolve_from(now, None, None), Ok(now));
+        assert_eq!(
+            resolve_from(now, Some(time(9, 30)), None),
+            Ok(Gap.with_ymd_and_hms(2026, 3, 29, 9, 30, 0).unwrap())
+        );
+        assert_eq!(
+            resolve_from(now, None, Some(TimeDelta::minutes(90))),
+            Ok(Gap.with_ymd_and_hms(2026, 3, 29, 10, 30, 0).unwrap())
+        );
+        assert_eq!(
+            resolve_from(now, Some(time(12, 30)), None),
+            Err("12:30 is in the future".to_owned())
+        );
+        assert_eq!(
+            resolve_from(now, Some(time(2, 30)), None),
+            Err("02:30:00 does not exist today".to_owned())
+        );
+        assert!(resolve_from(now, None, Some(TimeDelta::max_value())).is_err());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(
+            parse_time("9:30"),
+            Ok(NaiveTime::from_hms_opt(9, 30, 0).unwrap())
+        );
+        assert_eq!(
+            parse_time("14:05:30"),
+            Ok(NaiveTime::from_hms_opt(14, 5, 30).unwrap())
+        );
+        assert!(parse_time("25:00").is_err());
+        assert!(parse_time("noon").is_err());
+    }
+
+    #[test]
+    fn test_parse_date_time() {
+        let parsed = parse_date_time("2026-10-02 09:30").unwrap();
+        assert_eq!(parsed.date_naive(), "2026-10-02".parse().unwrap());
+        assert_eq!(parsed.time(), NaiveTime::from_hms_opt(9, 30, 0).unwrap());
+        assert!(parse_date_time("2026-10-02").is_err());
+        assert!(parse_date_time("09:30 2026-10-02").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("45m"), Ok(TimeDelta::minutes(45)));
+        assert_eq!(parse_duration("1h30m"), Ok(TimeDelta::minutes(90)));
+        assert_eq!(parse_duration("2h"), Ok(TimeDelta::hours(2)));
+        assert_eq!(parse_duration("90s"), Ok(TimeDelta::seconds(90)));
+        assert!(parse_duration("45").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("1d").is_err());
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("99999999999999h").is_err());
+        assert!(parse_duration("9223372036854775807s").is_err());
+        assert!(parse_duration("99999999999999999999s").is_err());
+    }
+}
@@ -0,0 +1,283 @@
+use chrono::{
+    prelude::{DateTime, Local},
+    Duration, TimeDelta,
+};
+use tui::{KeyCode, KeyModifiers, Screen};
+
+use crate::complete_current_task;
+use crate::config::Config;
+use crate::dir::Dir;
+use crate::error::Result;
+use crate::out::{target::TargetOut, task::pretty_duration};
+use crate::projects::Projects;
+use crate::task::Task;
+use crate::tasks::{ChronoUnit, Tasks};
+
+const TICK: std::time::Duration = std::time::Duration::from_secs(1);
+const HELP: &str = "s start  l switch  x stop  p pause/resume  r project  q quit";
+
+///Full screen view of the current task with a live timer and today's
+///entries. Keys start, stop and switch tasks.
+pub fn run(dir: &Dir, config: &Config, projects: &Projects) -> Result<()> {
+    let mut screen = Screen::enter();
+    let mut message = String::new();
+
+    loop {
+        screen.draw(&view(dir, config, Local::now(), &message));
+        let Some(key) = screen.key(TICK) else {
+            continue;
+        };
+        let result = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Char('s') => screen.suspend(|| start(dir, projects)),
+            KeyCode::Char('l') => screen.suspend(|| switch(dir, projects)),
+            KeyCode::Char('x') => stop(dir),
+            KeyCode::Char('p') => pause_or_resume(dir),
+            KeyCode::Char('r') => screen.suspend(|| change_project(dir, projects)),
+            _ => continue,
+        };
+        //A handled key shows the user is there, so the time isn't idle.
+        let result = dir.write_last_seen(Local::now()).and(result);
+        message = result.unwrap_or_else(|e| e.to_string());
+    }
+}
+
+//The lines of the dashboard. When the tracking files can't be read, for
+//example while another tlog is writing them, the error is shown instead of
+//the tasks and the next tick tries again.
+fn view(dir: &Dir, config: &Config, now: DateTime<Local>, message: &str) -> Vec<String> {
+    lines(dir, config, now, message).unwrap_or_else(|e| header(now, &e.to_string()))
+}
+
+fn header(now: DateTime<Local>, message: &str) -> Vec<String> {
+    vec![
+        format!("tlog  {}", now.format("%A %Y-%m-%d %H:%M:%S")),
+        HELP.to_owned(),
+        message.to_owned(),
+    ]
+}
+
+fn lines(dir: &Dir, config: &Config, now: DateTime<Local>, message: &str) -> Result<Vec<String>> {
+    let today = now.date_naive();
+    let mut lines = header(now, message);
+
+    let current = Task::from_current(dir)?;
+    match (&current, Task::from_paused(dir)?) {
+        (Some(task), _) => {
+            lines.push(format!("Current task: {}", describe(task)));
+            lines.push(format!(
+                "  Running since {}, {}",
+                task.start.format("%H:%M"),
+                clock(now - task.start)
+            ));
+        }
+        (None, Some(task)) => {
+            lines.push(format!("Paused task: {}", describe(&task)));
+            let paused_at = task.end.unwrap_or(task.start);
+            lines.push(format!("  Paused since {}", paused_at.format("%H:%M")));
+        }
+        (None, None) => lines.push("No active task.".to_owned()),
+    }
+
+    lines.push(String::new());
+    lines.push("Today:".to_owned());
+    let logged = Tasks::between(today, today, dir)?;
+    let mut entries: Vec<&Task> = logged.iter().flat_map(|t| &t.inner).collect();
+    entries.sort_by_key(|t| t.start);
+    if entries.is_empty() {
+        lines.push("  Nothing logged yet.".to_owned());
+    }
+    for task in entries {
+        let end = task
+            .end
+            .map_or("".to_owned(), |end| end.format("%H:%M").to_string());
+        lines.push(format!(
+            "  {}-{} {} {}",
+            task.start.format("%H:%M"),
+            end,
+            describe(task),
+            pretty_duration(Duration::seconds(task.time_spent_on(today)))
+        ));
+    }
+
+    let time_spent = logged.as_ref().map_or(0, Tasks::time_spent)
+        + current.as_ref().map_or(0, |t| t.time_spent_on(today));
+    lines.push(format!(
+        "Time spent today: {}",
+        clock(Duration::seconds(time_spent))
+    ));
+    if let Some(target) = config.targets.daily(today) {
+        lines.push(TargetOut::line("today", time_spent, target));
+    }
+    Ok(lines)
+}
+
+fn describe(task: &Task) -> String {
+    let ticket = match &task.ticket_number {
+        Some(t) => format!(" <{}>", t),
+        _ => "".to_owned(),
+    };
+    format!("{}{} [{}]", task.name, ticket, task.project)
+}
+
+//A duration as hours, minutes and seconds so the timer visibly ticks.
+fn clock(duration: TimeDelta) -> String {
+    let seconds = duration.num_seconds().max(0);
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn start(dir: &Dir, projects: &Projects) -> Result<String> {
+    let now = Local::now();
+    complete_current_task(dir, now)?;
+    let task = Task::new(dir, projects, false, now)?;
+    Ok(format!("Started {:?}.", task.name))
+}
+
+fn switch(dir: &Dir, projects: &Projects) -> Result<String> {
+    let Some(tasks) = Tasks::new(ChronoUnit::Month, dir)? else {
+        return Ok("No existing tasks to select from.".to_owned());
+    };
+    let names = tasks.get_names();
+    let Some(index) = tui::menu("Select task:", &names) else {
+        return Ok(String::new());
+    };
+    let Some(task) = tasks.get_latest_task_by_name(names[index]) else {
+        return Ok(String::new());
+    };
+    if !projects.is_active(&task.project) {
+        return Ok(format!("Project {:?} is archived.", task.project));
+    }
+
+    let now = Local::now();
+    complete_current_task(dir, now)?;
+    let mut task = task.clone();
+    task.start(now)?;
+    Ok(format!("Switched to {:?}.", task.name))
+}
+
+fn stop(dir: &Dir) -> Result<String> {
+    if let Some(mut task) = Task::from_current(dir)? {
+        task.complete(Local::now())?;
+        Ok(format!("Stopped {:?}.", task.name))
+    } else if let Some(task) = Task::from_paused(dir)? {
+        dir.remove_paused_file()?;
+        Ok(format!("Stopped paused task {:?}.", task.name))
+    } else {
+        Ok("No active task.".to_owned())
+    }
+}
+
+fn pause_or_resume(dir: &Dir) -> Result<String> {
+    if let Some(mut task) = Task::from_current(dir)? {
+        task.pause(Local::now())?;
+        Ok(format!("Paused {:?}.", task.name))
+    } else if let Some(mut task) = Task::from_paused(dir)? {
+        task.resume(Local::now())?;
+        Ok(format!("Resumed {:?}.", task.name))
+    } else {
+        Ok("No active task.".to_owned())
+    }
+}
+
+fn change_project(dir: &Dir, projects: &Projects) -> Result<String> {
+    let Some(mut task) = Task::from_current(dir)? else {
+        return Ok("No active task.".to_owned());
+    };
+    let names: Vec<&str> = projects.active().map(|p| p.name.as_str()).collect();
+    let Some(index) = tui::menu("Select project:", &names) else {
+        return Ok(String::new());
+    };
+
+    task.project = names[index].to_owned();
+    task.start(task.start)?;
+    Ok(format!("Moved {:?} to {}.", task.name, task.project))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use std::fs;
+
+    #[test]
+    fn test_clock() {
+        assert_eq!(clock(TimeDelta::zero()), "0:00:00");
+        assert_eq!(clock(TimeDelta::seconds(3725)), "1:02:05");
+        assert_eq!(clock(TimeDelta::hours(27)), "27:00:00");
+        assert_eq!(clock(TimeDelta::seconds(-5)), "0:00:00");
+    }
+
+    #[test]
+    fn test_lines_without_tasks() {
+        let (_tmp, dir) = testing::temp_dir();
+        let lines = lines(&dir, &Config::default(), testing::at(12), "Started.").unwrap();
+        assert_eq!(lines[1], HELP);
+        assert_eq!(lines[2], "Started.");
+        assert!(lines.contains(&"No active task.".to_owned()));
+        assert!(lines.contains(&"  Nothing logged yet.".to_owned()));
+        assert!(lines.contains(&"Time spent today: 0:00:00".to_owned()));
+    }
+
+    #[test]
+    fn test_lines_with_tasks() {
+        let (_tmp, dir) = testing::temp_dir();
+        testing::write_log(
+            &dir,
+            "2026",
+            "10",
+            &[
+                testing::row(
+                    "Write docs",
+                    "",
+                    "tlog",
+                    "2026-10-01 09:00",
+                    "2026-10-01 10:30",
+                ),
+                testing::row(
+                    "Standup",
+                    "",
+                    "tlog",
+                    "2026-09-30 09:00",
+                    "2026-09-30 09:15",
+                ),
+            ],
+        );
+        let start = testing::at(11).to_rfc3339();
+        fs::write(
+            &dir.current_file,
+            format!("{}Review,42,tlog,{start},\n", testing::HEADER),
+        )
+        .unwrap();
+
+        let now = testing::at(12) + TimeDelta::seconds(5);
+        let lines = lines(&dir, &Config::default(), now, "").unwrap();
+        assert!(lines.contains(&"Current task: Review <42> [tlog]".to_owned()));
+        assert!(lines.contains(&"  Running since 11:00, 1:00:05".to_owned()));
+        let today: Vec<&String> = lines.iter().filter(|l| l.starts_with("  09")).collect();
+        assert_eq!(today.len(), 1);
+        assert!(today[0].starts_with("  09:00-10:30 Write docs [tlog]"));
+    }
+
+    #[test]
+    fn test_view_shows_read_errors() {
+        let (_tmp, dir) = testing::temp_dir();
+        fs::write(
+            &dir.current_file,
+            format!("{}Review,,tlog,yesterday,\n", testing::HEADER),
+        )
+        .unwrap();
+
+        let now = testing::at(12);
+        assert!(lines(&dir, &Config::default(), now, "").is_err());
+        let view = view(&dir, &Config::default(), now, "");
+        assert_eq!(view.len(), 3);
+        assert_eq!(view[1], HELP);
+        assert!(!view[2].is_empty());
+    }
+}
@@ -0,0 +1,55 @@
+///Match the pattern against the text, case insensitively, with the
+///pattern's characters appearing in order but not necessarily next to each
+///other. Returns a score, higher is better, and the positions of the
+///matched characters in the text.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let mut positions = vec![];
+    let mut score = 0;
+    let mut pattern = pattern.chars().flat_map(char::to_lowercase).peekable();
+    let mut previous: Option<char> = None;
+
+    for (index, c) in text.chars().enumerate() {
+        let Some(wanted) = pattern.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(std::iter::once(*wanted)) {
+            score += 1;
+            //Consecutive matches and matches at the start of a word are
+            //what people usually type.
+            if positions.last().is_some_and(|last| last + 1 == index) {
+                score += 5;
+            }
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 3;
+            }
+            positions.push(index);
+            pattern.next();
+        }
+        previous = Some(c);
+    }
+
+    if pattern.peek().is_some() {
+        return None;
+    }
+    //Prefer matches that start early.
+    score -= positions.first().copied().unwrap_or_default() as i64;
+    Some((score, positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(fuzzy_match("", "abc"), Some((0, vec![])));
+        assert_eq!(fuzzy_match("ac", "abc").unwrap().1, vec![0, 2]);
+        assert_eq!(fuzzy_match("AC", "abc").unwrap().1, vec![0, 2]);
+        assert!(fuzzy_match("ca", "abc").is_none());
+        assert!(fuzzy_match("abcd", "abc").is_none());
+
+        let (consecutive, _) = fuzzy_match("fix", "fix login").unwrap();
+        let (scattered, _) = fuzzy_match("fix", "find index").unwrap();
+        assert!(consecutive > scattered);
+    }
+}
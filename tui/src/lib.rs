@@ -1,80 +1,457 @@
+mod fuzzy;
+mod screen;
+
 use crossterm::{
     cursor,
-    event::{read, Event, KeyCode, KeyModifiers},
+    event::{read, Event, KeyEventKind},
+    style::Stylize,
     terminal::{self, disable_raw_mode, enable_raw_mode, ClearType},
     QueueableCommand,
 };
 use std::io::{stdout, Write};
 
-pub fn menu<'a, S>(label: &str, items: &'a [S]) -> &'a S
+use fuzzy::fuzzy_match;
+pub use screen::Screen;
+
+pub use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+///Where menus read key presses from.
+pub trait Events {
+    ///The next key press, none when there is no more input.
+    fn next_key(&mut self) -> Option<KeyEvent>;
+}
+
+///Key presses from the terminal.
+pub struct TerminalEvents;
+
+impl Events for TerminalEvents {
+    fn next_key(&mut self) -> Option<KeyEvent> {
+        loop {
+            match read().ok()? {
+                Event::Key(event) if event.kind == KeyEventKind::Press => return Some(event),
+                _ => {}
+            }
+        }
+    }
+}
+
+//The terminal's columns and rows, with a default when it can't tell.
+fn terminal_size() -> (u16, u16) {
+    match terminal::size() {
+        Ok((columns, rows)) if columns > 0 && rows > 0 => (columns, rows),
+        _ => (80, 24),
+    }
+}
+
+///Let the user pick one of the items. Typing filters the items with fuzzy
+///matching. The arrow keys, Ctrl-n/Ctrl-p, Page Up/Down, Home and End move
+///the selection and Enter selects. Returns none when there are no items or
+///the user cancels with Esc or Ctrl-c, otherwise the index of the picked
+///item.
+pub fn menu<S>(label: &str, items: &[S]) -> Option<usize>
+where
+    S: AsRef<str> + std::fmt::Display,
+{
+    let _raw_mode = RawMode::enable();
+    menu_with(
+        label,
+        items,
+        &mut TerminalEvents,
+        &mut stdout(),
+        terminal_size(),
+    )
+}
+
+///`menu` reading keys from `events` and drawing on `out`, for a terminal of
+///the given columns and rows.
+pub fn menu_with<S>(
+    label: &str,
+    items: &[S],
+    events: &mut impl Events,
+    out: &mut impl Write,
+    size: (u16, u16),
+) -> Option<usize>
+where
+    S: AsRef<str> + std::fmt::Display,
+{
+    let selected = run(label, items, false, events, out, size)?;
+    selected.first().copied()
+}
+
+///Let the user pick any number of the items, in the same way as `menu`.
+///Space toggles the selected item and Enter confirms, picking the selected
+///item when none are toggled. The indexes of the picked items are returned
+///in the order the items were given.
+pub fn multi_select<S>(label: &str, items: &[S]) -> Option<Vec<usize>>
+where
+    S: AsRef<str> + std::fmt::Display,
+{
+    let _raw_mode = RawMode::enable();
+    multi_select_with(
+        label,
+        items,
+        &mut TerminalEvents,
+        &mut stdout(),
+        terminal_size(),
+    )
+}
+
+///`multi_select` reading keys from `events` and drawing on `out`, for a
+///terminal of the given columns and rows.
+pub fn multi_select_with<S>(
+    label: &str,
+    items: &[S],
+    events: &mut impl Events,
+    out: &mut impl Write,
+    size: (u16, u16),
+) -> Option<Vec<usize>>
+where
+    S: AsRef<str> + std::fmt::Display,
+{
+    run(label, items, true, events, out, size)
+}
+
+//Show the menu until the user selects or cancels, returns the indexes of
+//the selected items.
+fn run<S>(
+    label: &str,
+    items: &[S],
+    multiple: bool,
+    events: &mut impl Events,
+    out: &mut impl Write,
+    (columns, rows): (u16, u16),
+) -> Option<Vec<usize>>
 where
     S: AsRef<str> + std::fmt::Display,
 {
-    let _ = enable_raw_mode();
-    let mut stdout = stdout();
-    let len: u16 = items.len() as u16;
-    let _ = stdout.queue(terminal::ScrollUp(len));
-    let _ = stdout.queue(cursor::MoveUp(len));
-    let mut exit = false;
-    let mut position: usize = 0;
-    let mut selected_position = 0;
-
-    while !exit {
-        let _ = stdout.queue(cursor::SavePosition);
-        let _ = stdout.write(label.as_bytes());
-
-        items.iter().enumerate().for_each(|(index, item)| {
-            let _ = stdout.queue(cursor::MoveToColumn(0));
-            let _ = stdout.queue(cursor::MoveDown(1));
-
-            if index == position {
-                let _ = stdout.write(format!("* {}", item).as_bytes());
-            } else {
-                let _ = stdout.write(format!("- {}", item).as_bytes());
+    if items.is_empty() {
+        return None;
+    }
+
+    let mut menu = Menu::new(items, rows.saturating_sub(2).max(1) as usize, multiple);
+
+    //Make room below the cursor for the items.
+    let height = menu.height as u16;
+    let _ = out.queue(terminal::ScrollUp(height));
+    let _ = out.queue(cursor::MoveUp(height));
+    let _ = out.queue(cursor::SavePosition);
+
+    let selected = loop {
+        menu.render(out, label, columns as usize);
+
+        let Some(event) = events.next_key() else {
+            break None;
+        };
+        match menu.handle(event) {
+            Action::Select(indexes) => break Some(indexes),
+            Action::Cancel => break None,
+            Action::None => {}
+        }
+    };
+
+    let _ = out.queue(cursor::RestorePosition);
+    let _ = out.queue(terminal::Clear(ClearType::FromCursorDown));
+    let _ = out.flush();
+
+    selected
+}
+
+//Raw mode is turned off again when this is dropped, so the terminal is
+//usable after a panic too.
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> Self {
+        let _ = enable_raw_mode();
+        RawMode
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+    }
+}
+
+enum Action {
+    None,
+    Cancel,
+    ///Indexes of the selected items.
+    Select(Vec<usize>),
+}
+
+struct Menu<'a, S> {
+    items: &'a [S],
+    filter: String,
+    ///Items matching the filter, best match first, with the positions of
+    ///the matched characters.
+    matches: Vec<(usize, Vec<usize>)>,
+    ///Selected position in `matches`.
+    position: usize,
+    ///First match shown, the list scrolls when it's taller than `height`.
+    offset: usize,
+    height: usize,
+    ///Whether several items can be checked, `checked` has an entry for each
+    ///item when they can.
+    multiple: bool,
+    checked: Vec<bool>,
+}
+
+impl<'a, S> Menu<'a, S>
+where
+    S: AsRef<str> + std::fmt::Display,
+{
+    fn new(items: &'a [S], max_height: usize, multiple: bool) -> Self {
+        let mut menu = Menu {
+            items,
+            filter: String::new(),
+            matches: vec![],
+            position: 0,
+            offset: 0,
+            height: items.len().clamp(1, max_height),
+            multiple,
+            checked: vec![false; items.len()],
+        };
+        menu.apply_filter();
+        menu
+    }
+
+    fn apply_filter(&mut self) {
+        let mut matches: Vec<(i64, usize, Vec<usize>)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| {
+                let (score, positions) = fuzzy_match(&self.filter, item.as_ref())?;
+                Some((score, index, positions))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        self.matches = matches
+            .into_iter()
+            .map(|(_, index, positions)| (index, positions))
+            .collect();
+        self.position = 0;
+        self.offset = 0;
+    }
+
+    fn move_to(&mut self, position: usize) {
+        self.position = position.min(self.matches.len().saturating_sub(1));
+        if self.position < self.offset {
+            self.offset = self.position;
+        } else if self.position >= self.offset + self.height {
+            self.offset = self.position + 1 - self.height;
+        }
+    }
+
+    fn handle(&mut self, event: KeyEvent) -> Action {
+        let control = event.modifiers.contains(KeyModifiers::CONTROL);
+        match event.code {
+            KeyCode::Esc => return Action::Cancel,
+            KeyCode::Char('c') if control => return Action::Cancel,
+            KeyCode::Down | KeyCode::Tab => self.move_to(self.position + 1),
+            KeyCode::Char('n') if control => self.move_to(self.position + 1),
+            KeyCode::Up | KeyCode::BackTab => self.move_to(self.position.saturating_sub(1)),
+            KeyCode::Char('p') if control => self.move_to(self.position.saturating_sub(1)),
+            KeyCode::PageDown => self.move_to(self.position + self.height),
+            KeyCode::PageUp => self.move_to(self.position.saturating_sub(self.height)),
+            KeyCode::Home => self.move_to(0),
+            KeyCode::End => self.move_to(self.matches.len()),
+            //Without anything toggled Enter picks the selected item.
+            KeyCode::Enter if self.multiple && self.checked.contains(&true) => {
+                let checked = self.checked.iter().enumerate().filter(|(_, c)| **c);
+                return Action::Select(checked.map(|(index, _)| index).collect());
             }
-        });
-        let _ = stdout.queue(cursor::RestorePosition);
-        let _ = stdout.flush();
-
-        if let Event::Key(event) = read().unwrap() {
-            match event.code {
-                KeyCode::Esc | KeyCode::Char('q') => exit = true,
-                KeyCode::Char('c') => {
-                    if event.modifiers == KeyModifiers::CONTROL {
-                        exit = true
-                    }
-                }
-                KeyCode::Char('j') | KeyCode::Down => {
-                    if position < items.len() - 1 {
-                        position += 1
-                    }
+            KeyCode::Enter => {
+                if let Some((index, _)) = self.matches.get(self.position) {
+                    return Action::Select(vec![*index]);
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    position = position.saturating_sub(1);
-                }
-                KeyCode::Enter => {
-                    selected_position = position;
-                    exit = true;
+            }
+            KeyCode::Char(' ') if self.multiple => {
+                if let Some((index, _)) = self.matches.get(self.position) {
+                    self.checked[*index] = !self.checked[*index];
                 }
-                _ => {}
             }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.apply_filter();
+            }
+            KeyCode::Char(c) if !control => {
+                self.filter.push(c);
+                self.apply_filter();
+            }
+            _ => {}
         }
+        Action::None
     }
 
-    let _ = stdout.queue(terminal::Clear(ClearType::CurrentLine));
-    let _ = stdout.flush();
-    let _ = disable_raw_mode();
+    fn render(&self, out: &mut impl Write, label: &str, columns: usize) {
+        let _ = out.queue(cursor::RestorePosition);
+        let _ = out.queue(terminal::Clear(ClearType::FromCursorDown));
+        let _ = write!(out, "{} {}", label, self.filter);
+
+        let visible = self.matches.iter().enumerate().skip(self.offset);
+        for (position, (index, positions)) in visible.take(self.height) {
+            let _ = out.queue(cursor::MoveToColumn(0));
+            let _ = out.queue(cursor::MoveDown(1));
+            let marker = if position == self.position { '*' } else { '-' };
+            let _ = write!(out, "{} ", marker);
+            if self.multiple {
+                let check = if self.checked[*index] { 'x' } else { ' ' };
+                let _ = write!(out, "[{}] ", check);
+            }
+
+            let item = self.items[*index].to_string();
+            let width = columns.saturating_sub(if self.multiple { 6 } else { 2 });
+            for (i, c) in item.chars().take(width).enumerate() {
+                let _ = if positions.contains(&i) {
+                    write!(out, "{}", c.bold().underlined())
+                } else {
+                    write!(out, "{}", c)
+                };
+            }
+        }
+        if self.matches.is_empty() {
+            let _ = out.queue(cursor::MoveToColumn(0));
+            let _ = out.queue(cursor::MoveDown(1));
+            let _ = write!(out, "  No matches");
+        }
 
-    items.iter().enumerate().nth(selected_position).unwrap().1
+        //Leave the cursor after the filter text.
+        let _ = out.queue(cursor::RestorePosition);
+        let _ = out.queue(cursor::MoveRight(
+            (label.chars().count() + 1 + self.filter.chars().count()) as u16,
+        ));
+        let _ = out.flush();
+    }
 }
 
-//
-// Could not get tests working, read this blog, it will iprobably help
-// https://jmmv.dev/2020/12/unit-testing-a-console-app.html
-//
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    //Key presses from a script, the input ends when they run out.
+    struct Script(VecDeque<KeyEvent>);
+
+    impl Events for Script {
+        fn next_key(&mut self) -> Option<KeyEvent> {
+            self.0.pop_front()
+        }
+    }
+
+    fn script(keys: &[KeyCode]) -> Script {
+        let keys = keys
+            .iter()
+            .map(|key| KeyEvent::new(*key, KeyModifiers::NONE));
+        Script(keys.collect())
+    }
+
+    const ITEMS: [&str; 4] = ["alpha", "beta", "gamma", "delta"];
+
+    //Run the menu over the items in a terminal with 5 rows, so 3 items fit.
+    fn select<'a>(items: &'a [&'a str], keys: &[KeyCode]) -> Option<&'a str> {
+        let mut out = vec![];
+        menu_with("Pick:", items, &mut script(keys), &mut out, (80, 5)).map(|index| items[index])
+    }
+
+    #[test]
+    fn test_menu() {
+        use KeyCode::*;
+        assert_eq!(select(&ITEMS, &[Enter]), Some("alpha"));
+        assert_eq!(select(&ITEMS, &[Down, Down, Enter]), Some("gamma"));
+        assert_eq!(select(&ITEMS, &[Down, Up, Enter]), Some("alpha"));
+        assert_eq!(select(&ITEMS, &[Tab, BackTab, Tab, Enter]), Some("beta"));
+    }
+
+    #[test]
+    fn test_menu_boundaries() {
+        use KeyCode::*;
+        assert_eq!(select(&ITEMS, &[Up, Up, Enter]), Some("alpha"));
+        assert_eq!(
+            select(&ITEMS, &[Down, Down, Down, Down, Enter]),
+            Some("delta")
+        );
+        assert_eq!(select(&ITEMS, &[End, Enter]), Some("delta"));
+        assert_eq!(select(&ITEMS, &[End, Home, Enter]), Some("alpha"));
+        assert_eq!(select(&ITEMS, &[PageDown, Enter]), Some("delta"));
+        assert_eq!(select(&ITEMS, &[End, PageUp, Enter]), Some("alpha"));
+        assert_eq!(select(&ITEMS, &[PageDown, PageDown, Enter]), Some("delta"));
+    }
+
+    #[test]
+    fn test_menu_cancel() {
+        use KeyCode::*;
+        assert_eq!(select(&ITEMS, &[Esc]), None);
+        assert_eq!(select(&ITEMS, &[Down, Esc, Enter]), None);
+        assert_eq!(select(&ITEMS, &[Down]), None);
+
+        let mut out = vec![];
+        let ctrl_c = KeyEvent::new(Char('c'), KeyModifiers::CONTROL);
+        let mut events = Script(VecDeque::from([ctrl_c]));
+        assert_eq!(
+            menu_with("Pick:", &ITEMS, &mut events, &mut out, (80, 5)),
+            None
+        );
+    }
 
     #[test]
-    fn test_menu() {}
+    fn test_menu_empty() {
+        let items: [&str; 0] = [];
+        let mut events = script(&[KeyCode::Enter]);
+        let mut out = vec![];
+        assert_eq!(
+            menu_with("Pick:", &items, &mut events, &mut out, (80, 5)),
+            None
+        );
+        //Nothing is drawn and no keys are read.
+        assert!(out.is_empty());
+        assert_eq!(events.0.len(), 1);
+    }
+
+    #[test]
+    fn test_menu_filter() {
+        use KeyCode::*;
+        assert_eq!(select(&ITEMS, &[Char('d'), Enter]), Some("delta"));
+        assert_eq!(select(&ITEMS, &[Char('e'), Char('t'), Enter]), Some("beta"));
+        assert_eq!(
+            select(&ITEMS, &[Char('x'), Backspace, Enter]),
+            Some("alpha")
+        );
+        //Enter does nothing when nothing matches.
+        assert_eq!(select(&ITEMS, &[Char('x'), Enter, Esc]), None);
+    }
+
+    #[test]
+    fn test_menu_render() {
+        let items: Vec<String> = (0..10).map(|i| format!("item {i}")).collect();
+        let mut out = vec![];
+        let mut events = script(&[KeyCode::End, KeyCode::Enter]);
+        let selected = menu_with("Pick:", &items, &mut events, &mut out, (80, 5));
+        assert_eq!(selected, Some(9));
+
+        let out = String::from_utf8(out).unwrap();
+        let (first, last) = out.split_at(out.rfind("Pick:").unwrap());
+        assert!(first.contains("* item 0") && first.contains("- item 2"));
+        assert!(!first.contains("item 3"));
+        //Scrolled to the end, only the last three items are shown.
+        assert!(last.contains("- item 7") && last.contains("* item 9"));
+        assert!(!last.contains("item 6"));
+    }
+
+    #[test]
+    fn test_multi_select() {
+        use KeyCode::*;
+        let mut out = vec![];
+        let keys = [Down, Down, Char(' '), Up, Up, Char(' '), Enter];
+        let selected = multi_select_with("Pick:", &ITEMS, &mut script(&keys), &mut out, (80, 5));
+        assert_eq!(selected, Some(vec![0, 2]));
+
+        let keys = [Down, Char(' '), Char(' '), Enter];
+        let selected = multi_select_with("Pick:", &ITEMS, &mut script(&keys), &mut out, (80, 5));
+        assert_eq!(selected, Some(vec![1]));
+
+        let selected = multi_select_with("Pick:", &ITEMS, &mut script(&[Esc]), &mut out, (80, 5));
+        assert_eq!(selected, None);
+    }
 }
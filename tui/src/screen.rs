@@ -0,0 +1,74 @@
+use crossterm::{
+    cursor,
+    event::{poll, read, Event, KeyEvent, KeyEventKind},
+    terminal::{self, disable_raw_mode, enable_raw_mode, ClearType},
+    QueueableCommand,
+};
+use std::io::{stdout, Write};
+use std::time::Duration;
+
+///A full screen view on the alternate screen. The terminal is restored when
+///it's dropped, also after a panic.
+pub struct Screen;
+
+impl Screen {
+    pub fn enter() -> Self {
+        Self::show();
+        Screen
+    }
+
+    fn show() {
+        let _ = enable_raw_mode();
+        let mut stdout = stdout();
+        let _ = stdout.queue(terminal::EnterAlternateScreen);
+        let _ = stdout.queue(cursor::Hide);
+        let _ = stdout.flush();
+    }
+
+    fn hide() {
+        let mut stdout = stdout();
+        let _ = stdout.queue(cursor::Show);
+        let _ = stdout.queue(terminal::LeaveAlternateScreen);
+        let _ = stdout.flush();
+        let _ = disable_raw_mode();
+    }
+
+    ///Replace what's on screen with the lines, cutting off what doesn't fit.
+    pub fn draw(&mut self, lines: &[String]) {
+        let (columns, rows) = crate::terminal_size();
+        let mut stdout = stdout();
+        let _ = stdout.queue(cursor::MoveTo(0, 0));
+        let _ = stdout.queue(terminal::Clear(ClearType::All));
+        for line in lines.iter().take(rows as usize) {
+            let line: String = line.chars().take(columns as usize).collect();
+            let _ = write!(stdout, "{}\r\n", line);
+        }
+        let _ = stdout.flush();
+    }
+
+    ///Wait up to `timeout` for a key press.
+    pub fn key(&self, timeout: Duration) -> Option<KeyEvent> {
+        if !poll(timeout).ok()? {
+            return None;
+        }
+        match read().ok()? {
+            Event::Key(event) if event.kind == KeyEventKind::Press => Some(event),
+            _ => None,
+        }
+    }
+
+    ///Leave the full screen while `f` runs, for prompts that read lines or
+    ///show a menu.
+    pub fn suspend<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        Self::hide();
+        let result = f();
+        Self::show();
+        result
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        Self::hide();
+    }
+}